@@ -21,6 +21,12 @@ impl UniformCrossover {
     }
 }
 
+impl Default for UniformCrossover {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl CrossoverMethod for UniformCrossover {
     fn crossover(
             &self,
@@ -37,4 +43,203 @@ impl CrossoverMethod for UniformCrossover {
             .map(|(&a, &b)| if rng.gen_bool(0.5) { a } else { b })
             .collect()
     }
-}
\ No newline at end of file
+}
+
+/// A method of crossover that cuts both parents at `points` random places,
+/// and takes each segment alternatively from one parent and the other,
+/// starting with `parent_a`.
+#[derive(Clone, Debug)]
+pub struct KPointCrossover {
+    /// Number of cut points (at least `1`, and fewer than genes)
+    points: usize
+}
+
+impl KPointCrossover {
+    pub fn new(points: usize) -> Self {
+        assert!(points >= 1);
+
+        Self { points }
+    }
+}
+
+impl CrossoverMethod for KPointCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome
+    ) -> Chromosome {
+        assert_eq!(parent_a.len(), parent_b.len());
+        assert!(self.points < parent_a.len(), "not enough genes for {} cut points", self.points);
+
+        // Cutting before the first gene would leave an empty segment
+        let mut cuts: Vec<_> = rand::seq::index::sample(rng, parent_a.len() - 1, self.points)
+            .into_iter()
+            .map(|cut| cut + 1)
+            .collect();
+        cuts.sort_unstable();
+
+        let mut cuts = cuts.into_iter().peekable();
+        let mut from_a = true;
+
+        parent_a
+            .iter()
+            .zip(parent_b.iter())
+            .enumerate()
+            .map(|(index, (&a, &b))| {
+                if cuts.next_if_eq(&index).is_some() {
+                    from_a = !from_a;
+                }
+
+                if from_a { a } else { b }
+            })
+            .collect()
+    }
+}
+
+/// The k-point crossover with a single cut point.
+#[derive(Clone, Debug)]
+pub struct SinglePointCrossover;
+
+impl SinglePointCrossover {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for SinglePointCrossover {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CrossoverMethod for SinglePointCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome
+    ) -> Chromosome {
+        KPointCrossover::new(1).crossover(rng, parent_a, parent_b)
+    }
+}
+
+/// A method of crossover that computes each gene as a weighted average of the parents:
+/// `weight * a + (1 - weight) * b`.
+#[derive(Clone, Debug)]
+pub struct ArithmeticCrossover {
+    /// Weight of `parent_a` (between `0.0` and `1.0`)
+    weight: f32
+}
+
+impl ArithmeticCrossover {
+    pub fn new(weight: f32) -> Self {
+        assert!((0.0..=1.0).contains(&weight));
+
+        Self { weight }
+    }
+}
+
+impl CrossoverMethod for ArithmeticCrossover {
+    fn crossover(
+        &self,
+        _rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome
+    ) -> Chromosome {
+        assert_eq!(parent_a.len(), parent_b.len());
+
+        parent_a
+            .iter()
+            .zip(parent_b.iter())
+            .map(|(&a, &b)| self.weight * a + (1.0 - self.weight) * b)
+            .collect()
+    }
+}
+
+/// BLX-alpha: each gene is drawn uniformly from the range between the genes of the parents,
+/// widened on both sides by `alpha` times its width.
+#[derive(Clone, Debug)]
+pub struct BlendCrossover {
+    /// How much the range is widened (non-negative, `0.5` being usual)
+    alpha: f32
+}
+
+impl BlendCrossover {
+    pub fn new(alpha: f32) -> Self {
+        assert!(alpha >= 0.0);
+
+        Self { alpha }
+    }
+}
+
+impl CrossoverMethod for BlendCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome
+    ) -> Chromosome {
+        assert_eq!(parent_a.len(), parent_b.len());
+
+        parent_a
+            .iter()
+            .zip(parent_b.iter())
+            .map(|(&a, &b)| {
+                let margin = self.alpha * (a - b).abs();
+                let (low, high) = (a.min(b) - margin, a.max(b) + margin);
+
+                low + (high - low) * rng.gen::<f32>()
+            })
+            .collect()
+    }
+}
+
+/// Simulated Binary Crossover (SBX): each gene spreads around the average of the parents,
+/// the way a single-point crossover spreads the bits of binary genes.
+/// The higher `eta`, the closer the child stays to its parents.
+#[derive(Clone, Debug)]
+pub struct SimulatedBinaryCrossover {
+    /// Distribution index (non-negative, usually between `2.0` and `20.0`)
+    eta: f32
+}
+
+impl SimulatedBinaryCrossover {
+    pub fn new(eta: f32) -> Self {
+        assert!(eta >= 0.0);
+
+        Self { eta }
+    }
+}
+
+impl CrossoverMethod for SimulatedBinaryCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome
+    ) -> Chromosome {
+        assert_eq!(parent_a.len(), parent_b.len());
+
+        let exponent = 1.0 / (self.eta + 1.0);
+
+        parent_a
+            .iter()
+            .zip(parent_b.iter())
+            .map(|(&a, &b)| {
+                let u = rng.gen::<f32>();
+
+                let spread = if u <= 0.5 {
+                    (2.0 * u).powf(exponent)
+                } else {
+                    (1.0 / (2.0 * (1.0 - u))).powf(exponent)
+                };
+
+                // Either one of the two children of the usual formulation
+                let sign = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+
+                0.5 * (a + b) + sign * 0.5 * spread * (a - b)
+            })
+            .collect()
+    }
+}
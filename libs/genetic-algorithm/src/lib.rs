@@ -13,7 +13,9 @@ pub mod test;
 pub struct GeneticAlgorithm<S> {
     selection_method: S,
     crossover_method: Box<dyn CrossoverMethod>,
-    mutation_method: Box<dyn MutationMethod>
+    mutation_method: Box<dyn MutationMethod>,
+    /// Number of fittest individuals copied unchanged into the next generation
+    elitism: usize
 }
 
 impl<S> GeneticAlgorithm<S>
@@ -28,44 +30,78 @@ where
         Self { 
             selection_method, 
             crossover_method: Box::new(crossover_method),
-            mutation_method: Box::new(mutation_method)
+            mutation_method: Box::new(mutation_method),
+            elitism: 0
         }
     }
 
-    /// Given a population, selects, crosses over, and mutates each individual.
+    /// Copies the `count` fittest individuals of each generation unchanged into the next one,
+    /// so that the best individual can't be lost to crossover and mutation.
+    pub fn with_elitism(mut self, count: usize) -> Self {
+        self.elitism = count;
+        self
+    }
+
+    /// Given a population, selects, crosses over, and mutates each individual,
+    /// apart from the elites that are copied as they are.
     pub fn evolve<I>(&self, rng: &mut dyn RngCore, population: &[I]) -> (Vec<I>, Statistics)
     where 
         I: Individual,
     {
         assert!(!population.is_empty());
 
-        let new_population: Vec<I> = (0..population.len())
-            .map(|_| {
-                // Selection of two random parents
-                let parent_a = self
-                    .selection_method
-                    .select(rng, population)
-                    .chromosome();
-
-                let parent_b = self
-                    .selection_method
-                    .select(rng, population)
-                    .chromosome();
-
-                // Crossover
-                let mut child = self
-                    .crossover_method
-                    .crossover(rng, parent_a, parent_b);
-
-                // Mutation
-                self.mutation_method.mutate(rng, &mut child);
-
-                // Convert the Chromosome back to an Individual
-                I::create(child)
-            })
+        let elites = self.elitism.min(population.len());
+
+        // The fittest individuals go through unchanged
+        let mut ranked: Vec<_> = population.iter().collect();
+        ranked.sort_by(|a, b| b.fitness().total_cmp(&a.fitness()));
+
+        let mut new_population: Vec<I> = ranked[..elites]
+            .iter()
+            .map(|individual| I::create(individual.chromosome().clone()))
             .collect();
 
-        let stats = Statistics::new(population);
+        let children = population.len() - elites;
+
+        // Selection of two parents per remaining child, either all at once
+        // or right before breeding each child
+        let parents = if S::SELECTS_MANY {
+            self.selection_method.select_many(rng, population, 2 * children)
+        } else {
+            Vec::new()
+        };
+
+        let children = (0..children).map(|index| {
+            let (parent_a, parent_b) = if S::SELECTS_MANY {
+                (parents[2 * index], parents[2 * index + 1])
+            } else {
+                (
+                    self.selection_method.select(rng, population),
+                    self.selection_method.select(rng, population)
+                )
+            };
+
+            // Crossover
+            let mut child = self
+                .crossover_method
+                .crossover(rng, parent_a.chromosome(), parent_b.chromosome());
+
+            // Mutation
+            self.mutation_method.mutate(rng, &mut child);
+
+            // Convert the Chromosome back to an Individual
+            I::create(child)
+        });
+
+        new_population.extend(children);
+
+        let best = ranked[0].chromosome();
+
+        let mut stats = Statistics::new(population, self.mutation_method.solution_genes(best.len()));
+        stats.elites = elites;
+        stats.elite_retained = new_population
+            .iter()
+            .any(|individual| individual.chromosome().iter().eq(best.iter()));
 
         (new_population, stats)
     }
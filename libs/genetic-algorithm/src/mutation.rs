@@ -1,40 +1,240 @@
 use crate::*;
+use rand_distr::{Cauchy, StandardNormal};
 
 /// A method to add random mutations to an existing genome.
 pub trait MutationMethod {
     /// Given a child's chromosome, modify one or more genes from it.
     fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome);
+
+    /// Number of genes of a chromosome of `len` genes that encode the solution,
+    /// as opposed to parameters of the mutation carried alongside it.
+    fn solution_genes(&self, len: usize) -> usize {
+        len
+    }
 }
 
-/// A simple mutation method, that changes each genes with a given chance,
-/// and with a given magnitude.
-/// For `chance = 0.5` and `coeff = 2.0`, each gene will have a fifty-fifty
-/// chance to be added or substracted 2.
+/// A mutation method that changes each gene with a given chance,
+/// by adding a normally distributed perturbation of standard deviation `coeff`.
+/// Genes can optionally be kept within bounds.
 #[derive(Clone, Debug)]
 pub struct GaussianMutation {
     /// Probability of changing a gene (between `0.0` and `1.0`)
     chance: f32,
 
-    /// Magnitude of that change
-    coeff: f32
+    /// Standard deviation of that change
+    coeff: f32,
+
+    /// Range that mutated genes are clamped to, if any
+    bounds: Option<(f32, f32)>
 }
 
 impl GaussianMutation {
     pub fn new(chance: f32, coeff: f32) -> Self {
-        assert!(chance >= 0.0 && chance <= 1.0);
+        assert!((0.0..=1.0).contains(&chance));
+        assert!(coeff >= 0.0);
+
+        Self { chance, coeff, bounds: None }
+    }
+
+    /// Clamps mutated genes between `low` and `high`.
+    pub fn with_bounds(mut self, low: f32, high: f32) -> Self {
+        assert!(low <= high);
 
-        Self { chance, coeff }
+        self.bounds = Some((low, high));
+        self
     }
 }
 
 impl MutationMethod for GaussianMutation {
     fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
         for gene in child.iter_mut() {
-            let sign = if rng.gen_bool(0.5)  { -1.0 } else { 1.0 };
+            if rng.gen_bool(self.chance as _) {
+                *gene += self.coeff * rng.sample::<f32, _>(StandardNormal);
+
+                if let Some((low, high)) = self.bounds {
+                    *gene = gene.clamp(low, high);
+                }
+            }
+        }
+    }
+}
+
+/// A mutation method that changes each gene with a given chance,
+/// by adding a Cauchy distributed perturbation of given `scale`.
+/// Its heavy tails make for occasional large jumps, out of local optima.
+#[derive(Clone, Debug)]
+pub struct CauchyMutation {
+    /// Probability of changing a gene (between `0.0` and `1.0`)
+    chance: f32,
+
+    /// Median of the magnitude of that change (positive)
+    scale: f32
+}
+
+impl CauchyMutation {
+    pub fn new(chance: f32, scale: f32) -> Self {
+        assert!((0.0..=1.0).contains(&chance));
+        assert!(scale > 0.0);
+
+        Self { chance, scale }
+    }
+}
 
+impl MutationMethod for CauchyMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        let distribution = Cauchy::new(0.0, self.scale).unwrap();
+
+        for gene in child.iter_mut() {
             if rng.gen_bool(self.chance as _) {
-                *gene += sign * self.coeff * rng.gen::<f32>();
+                *gene += rng.sample::<f32, _>(distribution);
             }
         }
     }
-}
\ No newline at end of file
+}
+
+/// Polynomial mutation of bounded genes: each gene changes with a given chance,
+/// following a polynomial distribution that never leaves `low..=high`.
+/// The higher `eta`, the smaller the changes.
+#[derive(Clone, Debug)]
+pub struct PolynomialMutation {
+    /// Probability of changing a gene (between `0.0` and `1.0`)
+    chance: f32,
+
+    /// Distribution index (non-negative, usually between `20.0` and `100.0`)
+    eta: f32,
+
+    low: f32,
+    high: f32
+}
+
+impl PolynomialMutation {
+    pub fn new(chance: f32, eta: f32, low: f32, high: f32) -> Self {
+        assert!((0.0..=1.0).contains(&chance));
+        assert!(eta >= 0.0);
+        assert!(low < high);
+
+        Self { chance, eta, low, high }
+    }
+}
+
+impl MutationMethod for PolynomialMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        let range = self.high - self.low;
+        let exponent = 1.0 / (self.eta + 1.0);
+
+        for gene in child.iter_mut() {
+            if !rng.gen_bool(self.chance as _) {
+                continue;
+            }
+
+            let x = gene.clamp(self.low, self.high);
+            let u = rng.gen::<f32>();
+
+            // The closer to a bound, the less the gene can move towards it
+            let delta = if u < 0.5 {
+                let distance = (x - self.low) / range;
+                let value = 2.0 * u + (1.0 - 2.0 * u) * (1.0 - distance).powf(self.eta + 1.0);
+
+                value.powf(exponent) - 1.0
+            } else {
+                let distance = (self.high - x) / range;
+                let value = 2.0 * (1.0 - u) + 2.0 * (u - 0.5) * (1.0 - distance).powf(self.eta + 1.0);
+
+                1.0 - value.powf(exponent)
+            };
+
+            *gene = (x + delta * range).clamp(self.low, self.high);
+        }
+    }
+}
+
+/// How many step sizes a self-adaptive chromosome carries.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StepSizes {
+    /// A single step size, shared by every gene
+    Single,
+    /// One step size per gene
+    PerGene
+}
+
+impl StepSizes {
+    /// Number of strategy genes to append to `genes` genes.
+    pub fn count(self, genes: usize) -> usize {
+        match self {
+            Self::Single => 1,
+            Self::PerGene => genes,
+        }
+    }
+
+    /// Number of genes of a chromosome of `len` genes, strategy genes excluded.
+    pub fn genes(self, len: usize) -> usize {
+        match self {
+            Self::Single => {
+                assert!(len >= 1, "a chromosome with a single step size has at least one gene");
+                len - 1
+            }
+            Self::PerGene => {
+                assert!(len.is_multiple_of(2), "a chromosome with a step size per gene has an even length");
+                len / 2
+            }
+        }
+    }
+}
+
+/// A self-adaptive mutation, in the manner of evolution strategies: each chromosome ends
+/// with its own step sizes (see `StepSizes`), which mutate log-normally before perturbing
+/// every other gene with a normal distribution of that standard deviation.
+/// The mutation magnitude itself evolves, along with the genes.
+#[derive(Clone, Debug)]
+pub struct SelfAdaptiveMutation {
+    step_sizes: StepSizes,
+
+    /// Lowest step size, so that mutation never vanishes (non-negative)
+    min_step_size: f32
+}
+
+impl SelfAdaptiveMutation {
+    pub fn new(step_sizes: StepSizes) -> Self {
+        Self { step_sizes, min_step_size: 1e-4 }
+    }
+
+    pub fn with_min_step_size(mut self, min_step_size: f32) -> Self {
+        assert!(min_step_size >= 0.0);
+
+        self.min_step_size = min_step_size;
+        self
+    }
+}
+
+impl MutationMethod for SelfAdaptiveMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        let count = self.step_sizes.genes(child.len());
+        let (genes, step_sizes) = child.genes.split_at_mut(count);
+
+        // Usual learning rates: a common factor for every step size, and one for each of them
+        let n = count as f32;
+        let (global_rate, local_rate) = match self.step_sizes {
+            StepSizes::Single => (0.0, 1.0 / n.sqrt()),
+            StepSizes::PerGene => (1.0 / (2.0 * n).sqrt(), 1.0 / (2.0 * n.sqrt()).sqrt()),
+        };
+
+        let global = global_rate * rng.sample::<f32, _>(StandardNormal);
+
+        for step_size in step_sizes.iter_mut() {
+            let local = local_rate * rng.sample::<f32, _>(StandardNormal);
+
+            *step_size = (*step_size * (global + local).exp()).max(self.min_step_size);
+        }
+
+        for (index, gene) in genes.iter_mut().enumerate() {
+            let step_size = step_sizes[index * step_sizes.len() / count];
+
+            *gene += step_size * rng.sample::<f32, _>(StandardNormal);
+        }
+    }
+
+    /// Step sizes aren't part of the solution.
+    fn solution_genes(&self, len: usize) -> usize {
+        self.step_sizes.genes(len)
+    }
+}
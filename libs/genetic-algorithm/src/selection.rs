@@ -1,7 +1,14 @@
 use crate::*;
+use rand::distributions::{Distribution, WeightedError, WeightedIndex};
+use std::fmt;
 
 /// A method that can select a parent in a given population.
 pub trait SelectionMethod {
+    /// Whether `select_many` does better than selecting each individual independently,
+    /// in which case the genetic algorithm selects every parent before breeding.
+    /// Otherwise, each pair of parents is selected right before its child is bred.
+    const SELECTS_MANY: bool = false;
+
     /// Given a population, select an individual.
     fn select<'a, I>(
         &self, 
@@ -9,19 +16,165 @@ pub trait SelectionMethod {
         population: &'a [I]) -> &'a I
     where
         I: Individual;
+
+    /// Given a population, select `count` individuals.
+    /// By default, each of them is selected independently.
+    fn select_many<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        count: usize
+    ) -> Vec<&'a I>
+    where
+        I: Individual
+    {
+        (0..count)
+            .map(|_| self.select(rng, population))
+            .collect()
+    }
+}
+
+/// The reasons why a selection method can't select an individual.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SelectionError {
+    /// There is no individual to select.
+    EmptyPopulation,
+    /// The fitness of an individual is NaN or infinite.
+    InvalidFitness { index: usize, fitness: f32 },
+    /// The fitness of an individual is negative, even after shifting it.
+    NegativeFitness { index: usize, fitness: f32 },
+    /// The offset of `FitnessShift::Offset` is NaN or infinite.
+    InvalidOffset { offset: f32 },
+    /// The shifted fitnesses add up to more than `f32::MAX`.
+    FitnessOverflow,
+    /// The shifted fitnesses can't be turned into probabilities.
+    Weights(WeightedError),
+}
+
+impl fmt::Display for SelectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyPopulation => {
+                write!(f, "the population is empty")
+            }
+            Self::InvalidFitness { index, fitness } => {
+                write!(f, "individual {} has an invalid fitness: {}", index, fitness)
+            }
+            Self::NegativeFitness { index, fitness } => {
+                write!(f, "individual {} has a negative fitness: {}", index, fitness)
+            }
+            Self::InvalidOffset { offset } => {
+                write!(f, "the fitness offset is invalid: {}", offset)
+            }
+            Self::FitnessOverflow => {
+                write!(f, "the shifted fitnesses add up to more than f32::MAX")
+            }
+            Self::Weights(err) => {
+                write!(f, "the shifted fitnesses are invalid weights: {}", err)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SelectionError {}
+
+/// How fitnesses are shifted before spinning the roulette wheel.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum FitnessShift {
+    /// Fitnesses are used as they are, and must not be negative
+    #[default]
+    None,
+
+    /// The same offset is added to every fitness
+    Offset(f32),
+
+    /// The lowest fitness of the population is subtracted from every fitness,
+    /// so that the least fit individual is never selected
+    Windowing
+}
+
+impl FitnessShift {
+    /// Shifts the fitnesses of a population into selection weights,
+    /// or explains why they can't be turned into probabilities.
+    fn weights<I>(self, population: &[I]) -> Result<Vec<f32>, SelectionError>
+    where
+        I: Individual
+    {
+        if population.is_empty() {
+            return Err(SelectionError::EmptyPopulation);
+        }
+
+        let fitnesses: Vec<_> = population.iter().map(Individual::fitness).collect();
+
+        if let Some(index) = fitnesses.iter().position(|fitness| !fitness.is_finite()) {
+            return Err(SelectionError::InvalidFitness { index, fitness: fitnesses[index] });
+        }
+
+        let offset = match self {
+            Self::None => 0.0,
+            Self::Offset(offset) if offset.is_finite() => offset,
+            Self::Offset(offset) => return Err(SelectionError::InvalidOffset { offset }),
+            Self::Windowing => -fitnesses.iter().copied().fold(f32::INFINITY, f32::min),
+        };
+
+        let weights: Vec<_> = fitnesses.iter().map(|fitness| fitness + offset).collect();
+
+        if let Some(index) = weights.iter().position(|&weight| weight < 0.0) {
+            return Err(SelectionError::NegativeFitness { index, fitness: fitnesses[index] });
+        }
+
+        if !weights.iter().sum::<f32>().is_finite() {
+            return Err(SelectionError::FitnessOverflow);
+        }
+
+        Ok(weights)
+    }
 }
 
 /// A simple selection method, that selects parents randomly.
 /// The probability to be selected depends on the fitness.
-pub struct RouletteWheelSelection;
+/// When every fitness is zero, individuals are selected uniformly.
+#[derive(Clone, Debug, Default)]
+pub struct RouletteWheelSelection {
+    shift: FitnessShift
+}
 
 impl RouletteWheelSelection {
     pub fn new() -> Self {
-        Self
+        Self::with_shift(FitnessShift::None)
+    }
+
+    /// Initializes a selection that shifts fitnesses, to cope with negative ones.
+    pub fn with_shift(shift: FitnessShift) -> Self {
+        Self { shift }
+    }
+
+    /// Given a population, selects an individual,
+    /// or explains why the fitnesses can't be turned into probabilities.
+    pub fn try_select<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I]
+    ) -> Result<&'a I, SelectionError>
+    where
+        I: Individual
+    {
+        let weights = self.shift.weights(population)?;
+
+        if weights.iter().all(|&weight| weight == 0.0) {
+            return Ok(population.choose(rng).unwrap());
+        }
+
+        let index = WeightedIndex::new(&weights)
+            .map_err(SelectionError::Weights)?
+            .sample(rng);
+
+        Ok(&population[index])
     }
 }
 
 impl SelectionMethod for RouletteWheelSelection {
+    /// Panicking version of `try_select`.
     fn select<'a, I>(
         &self,
         rng: &mut dyn RngCore,
@@ -30,8 +183,240 @@ impl SelectionMethod for RouletteWheelSelection {
     where
         I: Individual
     {
-        population
-            .choose_weighted(rng, |individual| individual.fitness())
-            .expect("[ERROR] Empty population")
+        self.try_select(rng, population).unwrap_or_else(|err| panic!("{}", err))
     }
-}
\ No newline at end of file
+}
+
+
+/// A selection method that picks `size` random contestants,
+/// and lets the fittest one win with a given probability.
+/// Only the ranking of the fitnesses matters, not their scale.
+/// For `probability = 0.8`, the fittest contestant wins 80% of the time,
+/// the second one 80% of the remaining time, and so on.
+#[derive(Clone, Debug)]
+pub struct TournamentSelection {
+    /// Number of contestants, drawn with replacement (at least `1`)
+    size: usize,
+
+    /// Probability for the fittest contestant to win (between `0.0` and `1.0`)
+    probability: f32
+}
+
+impl TournamentSelection {
+    pub fn new(size: usize, probability: f32) -> Self {
+        assert!(size >= 1);
+        assert!((0.0..=1.0).contains(&probability));
+
+        Self { size, probability }
+    }
+}
+
+impl SelectionMethod for TournamentSelection {
+    fn select<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I]
+    ) -> &'a I
+    where
+        I: Individual
+    {
+        let mut contestants: Vec<_> = (0..self.size)
+            .map(|_| population.choose(rng).expect("[ERROR] Empty population"))
+            .collect();
+
+        // Fittest first
+        contestants.sort_by(|a, b| b.fitness().total_cmp(&a.fitness()));
+
+        let last = contestants.pop().unwrap();
+
+        contestants
+            .into_iter()
+            .find(|_| rng.gen_bool(self.probability as _))
+            .unwrap_or(last)
+    }
+}
+
+
+/// How the probability to be selected grows with the rank of an individual.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Ranking {
+    /// The fittest individual is `pressure` times as likely to be selected as an average one,
+    /// and the others decrease linearly down to `2.0 - pressure` (between `1.0` and `2.0`)
+    Linear { pressure: f32 },
+
+    /// Each individual is `base` times as likely to be selected as the next fittest one
+    /// (between `0.0` excluded and `1.0`)
+    Exponential { base: f32 }
+}
+
+/// A selection method where the probability to be selected depends
+/// on the rank of the fitness, rather than on the fitness itself.
+#[derive(Clone, Debug)]
+pub struct RankSelection {
+    ranking: Ranking
+}
+
+impl RankSelection {
+    pub fn linear(pressure: f32) -> Self {
+        assert!((1.0..=2.0).contains(&pressure));
+
+        Self { ranking: Ranking::Linear { pressure } }
+    }
+
+    pub fn exponential(base: f32) -> Self {
+        assert!(base > 0.0 && base <= 1.0);
+
+        Self { ranking: Ranking::Exponential { base } }
+    }
+
+    /// Weight of the individual of given rank, `0` being the least fit out of `len`.
+    fn weight(&self, rank: usize, len: usize) -> f32 {
+        match self.ranking {
+            Ranking::Linear { pressure } => {
+                (2.0 - pressure) + 2.0 * (pressure - 1.0) * rank as f32 / (len - 1) as f32
+            }
+            Ranking::Exponential { base } => base.powi((len - 1 - rank) as i32),
+        }
+    }
+}
+
+impl SelectionMethod for RankSelection {
+    const SELECTS_MANY: bool = true;
+
+    fn select<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I]
+    ) -> &'a I
+    where
+        I: Individual
+    {
+        self.select_many(rng, population, 1)[0]
+    }
+
+    /// Ranks the population only once for every selected individual.
+    fn select_many<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        count: usize
+    ) -> Vec<&'a I>
+    where
+        I: Individual
+    {
+        assert!(!population.is_empty(), "[ERROR] Empty population");
+
+        if population.len() == 1 {
+            return vec![&population[0]; count];
+        }
+
+        // Least fit first
+        let mut ranked: Vec<_> = population.iter().collect();
+        ranked.sort_by(|a, b| a.fitness().total_cmp(&b.fitness()));
+
+        let ranked: Vec<_> = ranked
+            .into_iter()
+            .enumerate()
+            .map(|(rank, individual)| (individual, self.weight(rank, population.len())))
+            .collect();
+
+        (0..count)
+            .map(|_| {
+                ranked
+                    .choose_weighted(rng, |(_, weight)| *weight)
+                    .expect("[ERROR] Empty population")
+                    .0
+            })
+            .collect()
+    }
+}
+
+/// Stochastic Universal Sampling: like the roulette wheel, the probability to be selected
+/// depends on the fitness, but all the individuals are selected in a single spin,
+/// with evenly spaced pointers. Each individual is selected about as many times
+/// as its share of the total fitness, with much less variance.
+/// When every fitness is zero, individuals are selected uniformly.
+#[derive(Clone, Debug, Default)]
+pub struct StochasticUniversalSampling {
+    shift: FitnessShift
+}
+
+impl StochasticUniversalSampling {
+    pub fn new() -> Self {
+        Self::with_shift(FitnessShift::None)
+    }
+
+    /// Initializes a selection that shifts fitnesses, to cope with negative ones.
+    pub fn with_shift(shift: FitnessShift) -> Self {
+        Self { shift }
+    }
+
+    /// Given a population, selects `count` individuals in a single spin,
+    /// or explains why the fitnesses can't be turned into probabilities.
+    /// The selected individuals are shuffled, so that neighbours aren't paired together.
+    pub fn try_select_many<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        count: usize
+    ) -> Result<Vec<&'a I>, SelectionError>
+    where
+        I: Individual
+    {
+        let weights = self.shift.weights(population)?;
+        let total: f32 = weights.iter().sum();
+
+        if total == 0.0 {
+            return Ok((0..count).map(|_| population.choose(rng).unwrap()).collect());
+        }
+
+        let spacing = total / count as f32;
+        let start = rng.gen::<f32>() * spacing;
+
+        let mut selected = Vec::with_capacity(count);
+        let mut index = 0;
+        let mut cumulative = weights[0];
+
+        for pointer in 0..count {
+            let pointer = start + pointer as f32 * spacing;
+
+            while cumulative <= pointer && index + 1 < population.len() {
+                index += 1;
+                cumulative += weights[index];
+            }
+
+            selected.push(&population[index]);
+        }
+
+        selected.shuffle(rng);
+        Ok(selected)
+    }
+}
+
+impl SelectionMethod for StochasticUniversalSampling {
+    const SELECTS_MANY: bool = true;
+
+    fn select<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I]
+    ) -> &'a I
+    where
+        I: Individual
+    {
+        self.select_many(rng, population, 1)[0]
+    }
+
+    /// Panicking version of `try_select_many`.
+    fn select_many<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        count: usize
+    ) -> Vec<&'a I>
+    where
+        I: Individual
+    {
+        self.try_select_many(rng, population, count).unwrap_or_else(|err| panic!("{}", err))
+    }
+}
@@ -1,38 +1,89 @@
 use crate::*;
 
+/// A summary of the fitnesses and of the genes of a population,
+/// to follow its progress and to detect premature convergence.
 #[derive(Clone, Debug)]
 pub struct Statistics {
     min_fitness: f32,
     max_fitness: f32,
-    average_fitness: f32
+    average_fitness: f32,
+    median_fitness: f32,
+    std_dev_fitness: f32,
+    /// Every fitness of the population, in increasing order
+    fitnesses: Vec<f32>,
+    /// Variance of each gene across the population
+    gene_variances: Vec<f32>,
+    /// Chromosomes of the population, to compare them on demand
+    chromosomes: Vec<Chromosome>,
+    /// Number of fittest individuals copied unchanged into the next generation
+    pub(crate) elites: usize,
+    /// Whether the chromosome of the fittest individual is found in the next generation
+    pub(crate) elite_retained: bool
 }
 
 impl Statistics {
-    pub fn new<I>(population: &[I]) -> Self 
+    /// Summarizes `population`, only looking at the first `genes` genes of each chromosome,
+    /// for instance to leave out parameters of the mutation carried in the chromosomes
+    /// (see `MutationMethod::solution_genes`).
+    pub fn new<I>(population: &[I], genes: usize) -> Self
     where
         I: Individual
     {
         assert!(!population.is_empty());
 
-        let mut min_fitness = population[0].fitness();
-        let mut max_fitness = population[0].fitness();
-        let mut sum_fitness = 0.0;
+        let mut fitnesses: Vec<_> = population.iter().map(Individual::fitness).collect();
+        fitnesses.sort_by(f32::total_cmp);
 
-        for individual in population {
-            let fitness = individual.fitness();
+        let len = fitnesses.len() as f32;
+        let average_fitness = fitnesses.iter().sum::<f32>() / len;
+        let variance = fitnesses
+            .iter()
+            .map(|fitness| (fitness - average_fitness).powi(2))
+            .sum::<f32>() / len;
 
-            min_fitness = min_fitness.min(fitness);
-            max_fitness = max_fitness.max(fitness);
-            sum_fitness += fitness;
-        }
+        let chromosomes: Vec<_> = population
+            .iter()
+            .map(|individual| individual.chromosome().iter().take(genes).copied().collect())
+            .collect();
 
         Self {
-            min_fitness,
-            max_fitness,
-            average_fitness: sum_fitness / (population.len() as f32)
+            min_fitness: fitnesses[0],
+            max_fitness: fitnesses[fitnesses.len() - 1],
+            average_fitness,
+            median_fitness: Self::interpolate(&fitnesses, 0.5),
+            std_dev_fitness: variance.sqrt(),
+            gene_variances: Self::compute_gene_variances(&chromosomes),
+            chromosomes,
+            fitnesses,
+            elites: 0,
+            elite_retained: false
         }
     }
 
+    /// Value at `rank` (between `0.0` and `1.0`) of sorted `values`,
+    /// linearly interpolated between the closest two.
+    fn interpolate(values: &[f32], rank: f32) -> f32 {
+        let position = rank * (values.len() - 1) as f32;
+        let (low, high) = (position.floor() as usize, position.ceil() as usize);
+
+        values[low] + (values[high] - values[low]) * (position - low as f32)
+    }
+
+    fn compute_gene_variances(chromosomes: &[Chromosome]) -> Vec<f32> {
+        let len = chromosomes.len() as f32;
+
+        (0..chromosomes[0].len())
+            .map(|gene| {
+                let mean = chromosomes.iter().map(|chromosome| chromosome[gene]).sum::<f32>() / len;
+
+                chromosomes
+                    .iter()
+                    .map(|chromosome| (chromosome[gene] - mean).powi(2))
+                    .sum::<f32>() / len
+            })
+            .collect()
+    }
+
     pub fn min_fitness(&self) -> f32 {
         self.min_fitness
     }
@@ -44,4 +95,92 @@ impl Statistics {
     pub fn avg_fitness(&self) -> f32 {
         self.average_fitness
     }
-}
\ No newline at end of file
+
+    pub fn median_fitness(&self) -> f32 {
+        self.median_fitness
+    }
+
+    /// Standard deviation of the fitnesses of the population
+    pub fn std_dev_fitness(&self) -> f32 {
+        self.std_dev_fitness
+    }
+
+    /// Fitness below which `percentile` percent of the population lies
+    /// (`percentile` between `0.0` and `100.0`), interpolated between individuals.
+    pub fn percentile_fitness(&self, percentile: f32) -> f32 {
+        assert!((0.0..=100.0).contains(&percentile));
+
+        Self::interpolate(&self.fitnesses, percentile / 100.0)
+    }
+
+    /// Number of individuals in each of `bins` equal ranges between the min and max fitnesses.
+    /// When every fitness is the same, the whole population falls in the first bin.
+    pub fn fitness_histogram(&self, bins: usize) -> Vec<usize> {
+        assert!(bins > 0);
+
+        let width = (self.max_fitness - self.min_fitness) / bins as f32;
+        let mut histogram = vec![0; bins];
+
+        for fitness in &self.fitnesses {
+            let bin = if width > 0.0 {
+                ((fitness - self.min_fitness) / width) as usize
+            } else {
+                0
+            };
+
+            // The max fitness closes the last range
+            histogram[bin.min(bins - 1)] += 1;
+        }
+
+        histogram
+    }
+
+    /// Variance of each gene across the population
+    pub fn gene_variances(&self) -> &[f32] {
+        &self.gene_variances
+    }
+
+    /// Average variance of the genes, which drops towards zero as the population converges
+    pub fn mean_gene_variance(&self) -> f32 {
+        if self.gene_variances.is_empty() {
+            return 0.0;
+        }
+
+        self.gene_variances.iter().sum::<f32>() / self.gene_variances.len() as f32
+    }
+
+    /// Average Euclidean distance between the chromosomes of two individuals.
+    /// It compares every pair of individuals, so it's only computed when asked for.
+    pub fn mean_pairwise_distance(&self) -> f32 {
+        let mut sum = 0.0;
+        let mut pairs = 0;
+
+        for (index, a) in self.chromosomes.iter().enumerate() {
+            for b in &self.chromosomes[index + 1..] {
+                sum += a
+                    .iter()
+                    .zip(b.iter())
+                    .map(|(a, b)| (a - b).powi(2))
+                    .sum::<f32>()
+                    .sqrt();
+                pairs += 1;
+            }
+        }
+
+        if pairs == 0 {
+            return 0.0;
+        }
+
+        sum / pairs as f32
+    }
+
+    pub fn elites(&self) -> usize {
+        self.elites
+    }
+
+    /// Whether the chromosome of the fittest individual made it unchanged
+    /// into the next generation, be it as an elite or by chance.
+    pub fn elite_retained(&self) -> bool {
+        self.elite_retained
+    }
+}
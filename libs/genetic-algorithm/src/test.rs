@@ -90,6 +90,447 @@ mod tests {
 
             assert_eq!(actual_histogram, expected_histogram);
         }
+
+        mod roulette_wheel {
+            use super::*;
+
+            fn histogram(method: RouletteWheelSelection, fitnesses: &[f32]) -> BTreeMap<i32, i32> {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let population: Vec<_> = fitnesses.iter().copied().map(TestIndividual::new).collect();
+                let mut histogram = BTreeMap::new();
+
+                for _ in 0..1_000 {
+                    let fitness = method
+                        .try_select(&mut rng, &population)
+                        .unwrap()
+                        .fitness() as i32;
+
+                    *histogram
+                        .entry(fitness)
+                        .or_insert(0) += 1;
+                }
+
+                histogram
+            }
+
+            #[test]
+            fn zero_fitnesses_are_selected_uniformly() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let method = RouletteWheelSelection::new();
+                let population = vec![TestIndividual::new(0.0); 4];
+                let mut histogram = [0; 4];
+
+                for _ in 0..1_000 {
+                    let selected = method.select(&mut rng, &population);
+                    let index = population.iter().position(|individual| std::ptr::eq(individual, selected));
+
+                    histogram[index.unwrap()] += 1;
+                }
+
+                assert_eq!(histogram, [246, 262, 254, 238]);
+            }
+
+            #[test]
+            fn negative_fitness() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let population = vec![TestIndividual::new(2.0), TestIndividual::new(-1.0)];
+
+                assert_eq!(
+                    RouletteWheelSelection::new().try_select(&mut rng, &population),
+                    Err(SelectionError::NegativeFitness { index: 1, fitness: -1.0 })
+                );
+                let method = RouletteWheelSelection::with_shift(FitnessShift::Offset(0.5));
+
+                assert_eq!(
+                    method.try_select(&mut rng, &population),
+                    Err(SelectionError::NegativeFitness { index: 1, fitness: -1.0 })
+                );
+            }
+
+            #[test]
+            fn offset() {
+                // Weights of 1, 2, 3 and 4
+                let method = RouletteWheelSelection::with_shift(FitnessShift::Offset(2.0));
+                let actual = histogram(method, &[-1.0, 0.0, 1.0, 2.0]);
+                let expected = BTreeMap::from_iter(vec![
+                    (-1, 102),
+                    (0, 198),
+                    (1, 301),
+                    (2, 399),
+                ]);
+
+                assert_eq!(actual, expected);
+            }
+
+            #[test]
+            fn windowing() {
+                // Weights of 0, 1, 2 and 3: the least fit individual is never selected
+                let method = RouletteWheelSelection::with_shift(FitnessShift::Windowing);
+                let actual = histogram(method, &[-3.0, -2.0, -1.0, 0.0]);
+                let expected = BTreeMap::from_iter(vec![
+                    (-2, 164),
+                    (-1, 337),
+                    (0, 499),
+                ]);
+
+                assert_eq!(actual, expected);
+            }
+
+            #[test]
+            fn windowing_equal_fitnesses_are_selected_uniformly() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let method = RouletteWheelSelection::with_shift(FitnessShift::Windowing);
+                let population = vec![TestIndividual::new(-3.0); 2];
+                let mut histogram = [0; 2];
+
+                for _ in 0..1_000 {
+                    let selected = method.select(&mut rng, &population);
+                    let index = population.iter().position(|individual| std::ptr::eq(individual, selected));
+
+                    histogram[index.unwrap()] += 1;
+                }
+
+                assert_eq!(histogram, [479, 521]);
+            }
+
+            #[test]
+            fn empty_population() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let population: Vec<TestIndividual> = Vec::new();
+
+                assert_eq!(
+                    RouletteWheelSelection::new().try_select(&mut rng, &population),
+                    Err(SelectionError::EmptyPopulation)
+                );
+            }
+
+            #[test]
+            fn invalid_fitness() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let population = vec![TestIndividual::new(1.0), TestIndividual::new(f32::INFINITY)];
+
+                let method = RouletteWheelSelection::with_shift(FitnessShift::Windowing);
+
+                assert_eq!(
+                    method.try_select(&mut rng, &population),
+                    Err(SelectionError::InvalidFitness { index: 1, fitness: f32::INFINITY })
+                );
+            }
+
+            #[test]
+            fn invalid_offset() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let population = vec![TestIndividual::new(1.0), TestIndividual::new(2.0)];
+
+                for offset in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+                    let method = RouletteWheelSelection::with_shift(FitnessShift::Offset(offset));
+
+                    assert!(matches!(
+                        method.try_select(&mut rng, &population),
+                        Err(SelectionError::InvalidOffset { .. })
+                    ));
+                }
+            }
+
+            #[test]
+            fn fitness_overflow() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let population = vec![TestIndividual::new(f32::MAX), TestIndividual::new(f32::MAX)];
+
+                assert_eq!(
+                    RouletteWheelSelection::new().try_select(&mut rng, &population),
+                    Err(SelectionError::FitnessOverflow)
+                );
+            }
+
+            #[test]
+            #[should_panic(expected = "individual 1 has a negative fitness: -1")]
+            fn select_panics_on_negative_fitness() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let population = vec![TestIndividual::new(2.0), TestIndividual::new(-1.0)];
+
+                RouletteWheelSelection::new().select(&mut rng, &population);
+            }
+        }
+
+        mod rank {
+            use super::*;
+
+            fn histogram(method: RankSelection) -> BTreeMap<i32, usize> {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+                // Ranks don't care about how far apart the fitnesses are
+                let population = vec![
+                    TestIndividual::new(2.0),
+                    TestIndividual::new(1.0),
+                    TestIndividual::new(400.0),
+                    TestIndividual::new(3.0),
+                ];
+
+                let mut histogram = BTreeMap::new();
+
+                for individual in method.select_many(&mut rng, &population, 1_000) {
+                    *histogram
+                        .entry(individual.fitness() as i32)
+                        .or_insert(0) += 1;
+                }
+
+                histogram
+            }
+
+            #[test]
+            fn linear() {
+                // Weights of 0, 2/3, 4/3 and 2: 0%, 17%, 33%, 50%
+                let actual = histogram(RankSelection::linear(2.0));
+                let expected = BTreeMap::from_iter(vec![
+                    (2, 164),
+                    (3, 337),
+                    (400, 499),
+                ]);
+
+                assert_eq!(actual, expected);
+            }
+
+            #[test]
+            fn linear_without_pressure_is_uniform() {
+                let actual = histogram(RankSelection::linear(1.0));
+                let expected = BTreeMap::from_iter(vec![
+                    (1, 248),
+                    (2, 253),
+                    (3, 270),
+                    (400, 229),
+                ]);
+
+                assert_eq!(actual, expected);
+            }
+
+            #[test]
+            fn exponential() {
+                // Weights of 1/8, 1/4, 1/2 and 1: 7%, 13%, 27%, 53%
+                let actual = histogram(RankSelection::exponential(0.5));
+                let expected = BTreeMap::from_iter(vec![
+                    (1, 72),
+                    (2, 130),
+                    (3, 273),
+                    (400, 525),
+                ]);
+
+                assert_eq!(actual, expected);
+            }
+
+            #[test]
+            fn select_is_select_many_of_one() {
+                let method = RankSelection::exponential(0.5);
+                let population = vec![TestIndividual::new(2.0), TestIndividual::new(1.0)];
+
+                let mut rng_a = ChaCha8Rng::from_seed(Default::default());
+                let mut rng_b = ChaCha8Rng::from_seed(Default::default());
+
+                for _ in 0..10 {
+                    assert_eq!(
+                        method.select(&mut rng_a, &population),
+                        method.select_many(&mut rng_b, &population, 1)[0]
+                    );
+                }
+            }
+        }
+
+        mod stochastic_universal_sampling {
+            use super::*;
+
+            #[test]
+            fn selects_each_individual_its_share_of_times() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let method = StochasticUniversalSampling::new();
+
+                let population = vec![
+                    TestIndividual::new(2.0),
+                    TestIndividual::new(1.0),
+                    TestIndividual::new(4.0),
+                    TestIndividual::new(3.0),
+                ];
+
+                // As many pointers as the total fitness, whatever the spin:
+                // each individual is selected exactly as many times as its fitness
+                for _ in 0..100 {
+                    let mut histogram = BTreeMap::new();
+
+                    for individual in method.select_many(&mut rng, &population, 10) {
+                        *histogram
+                            .entry(individual.fitness() as i32)
+                            .or_insert(0) += 1;
+                    }
+
+                    let expected = BTreeMap::from_iter(vec![
+                        (1, 1),
+                        (2, 2),
+                        (3, 3),
+                        (4, 4),
+                    ]);
+
+                    assert_eq!(histogram, expected);
+                }
+            }
+
+            /// A single pointer spins just like a roulette wheel
+            #[test]
+            fn test() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let method = StochasticUniversalSampling::new();
+
+                let population = vec![
+                    TestIndividual::new(2.0),
+                    TestIndividual::new(1.0),
+                    TestIndividual::new(4.0),
+                    TestIndividual::new(3.0),
+                ];
+
+                let mut histogram = BTreeMap::new();
+
+                for _ in 0..1_000 {
+                    let fitness = method
+                        .select(&mut rng, &population)
+                        .fitness() as i32;
+
+                    *histogram
+                        .entry(fitness)
+                        .or_insert(0) += 1;
+                }
+
+                let expected = BTreeMap::from_iter(vec![
+                    (1, 98),
+                    (2, 202),
+                    (3, 278),
+                    (4, 422),
+                ]);
+
+                assert_eq!(histogram, expected);
+            }
+
+            #[test]
+            fn zero_fitnesses_are_selected_uniformly() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let method = StochasticUniversalSampling::new();
+                let population = vec![TestIndividual::new(0.0); 4];
+                let mut histogram = [0; 4];
+
+                for selected in method.select_many(&mut rng, &population, 1_000) {
+                    let index = population.iter().position(|individual| std::ptr::eq(individual, selected));
+
+                    histogram[index.unwrap()] += 1;
+                }
+
+                assert_eq!(histogram, [246, 262, 254, 238]);
+            }
+
+            #[test]
+            fn negative_fitness() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let population = vec![TestIndividual::new(2.0), TestIndividual::new(-1.0)];
+
+                assert_eq!(
+                    StochasticUniversalSampling::new().try_select_many(&mut rng, &population, 2),
+                    Err(SelectionError::NegativeFitness { index: 1, fitness: -1.0 })
+                );
+
+                // Weights of 0 and 3: only the first individual is selected
+                let method = StochasticUniversalSampling::with_shift(FitnessShift::Offset(1.0));
+                let selected = method.try_select_many(&mut rng, &population, 3).unwrap();
+
+                assert!(selected.iter().all(|individual| individual.fitness() == 2.0));
+            }
+        }
+
+        mod tournament {
+            use super::*;
+
+            fn histogram(method: TournamentSelection) -> BTreeMap<i32, i32> {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+                let population = vec![
+                    TestIndividual::new(2.0),
+                    TestIndividual::new(1.0),
+                    TestIndividual::new(4.0),
+                    TestIndividual::new(3.0),
+                ];
+
+                let mut histogram = BTreeMap::new();
+
+                for _ in 0..1_000 {
+                    let fitness = method
+                        .select(&mut rng, &population)
+                        .fitness() as i32;
+
+                    *histogram
+                        .entry(fitness)
+                        .or_insert(0) += 1;
+                }
+
+                histogram
+            }
+
+            #[test]
+            fn test() {
+                // With 2 contestants out of 4 and p = 0.75, the k-th fittest individual
+                // is expected to win (13 - 2k) / 32 of the time: 34%, 28%, 22%, 16%
+                let actual = histogram(TournamentSelection::new(2, 0.75));
+                let expected = BTreeMap::from_iter(vec![
+                    (1, 169),
+                    (2, 206),
+                    (3, 264),
+                    (4, 361),
+                ]);
+
+                assert_eq!(actual, expected);
+            }
+
+            #[test]
+            fn single_contestant_is_uniform() {
+                let actual = histogram(TournamentSelection::new(1, 1.0));
+                let expected = BTreeMap::from_iter(vec![
+                    (1, 262),
+                    (2, 246),
+                    (3, 238),
+                    (4, 254),
+                ]);
+
+                assert_eq!(actual, expected);
+            }
+
+            #[test]
+            fn certain_win_with_many_contestants_picks_the_fittest() {
+                let actual = histogram(TournamentSelection::new(50, 1.0));
+                let expected = BTreeMap::from_iter(vec![(4, 1_000)]);
+
+                assert_eq!(actual, expected);
+            }
+
+            #[test]
+            fn is_insensitive_to_fitness_scale() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let method = TournamentSelection::new(2, 1.0);
+
+                // The outlier can't take over every tournament
+                let population = vec![
+                    TestIndividual::new(1.0),
+                    TestIndividual::new(2.0),
+                    TestIndividual::new(1_000_000.0),
+                ];
+
+                let outliers = (0..1_000)
+                    .filter(|_| method.select(&mut rng, &population).fitness() > 2.0)
+                    .count();
+
+                // 1 - (2/3)^2 = 5/9 of the tournaments include the outlier
+                assert_eq!(outliers, 556);
+            }
+
+            #[test]
+            #[should_panic]
+            fn invalid_probability() {
+                TournamentSelection::new(2, 1.5);
+            }
+        }
     }
 
     fn chromosome() -> Chromosome {
@@ -216,6 +657,202 @@ mod tests {
             assert_eq!(diff_a, 49);
             assert_eq!(diff_b, 51);
         }
+
+        fn parents() -> (Chromosome, Chromosome) {
+            let parent_a = (1..=100).map(|n| n as f32).collect();
+            let parent_b = (1..=100).map(|n| -n as f32).collect();
+
+            (parent_a, parent_b)
+        }
+
+        /// Indices where the child switches from one parent to the other.
+        fn cuts(child: &Chromosome) -> Vec<usize> {
+            (1..child.len())
+                .filter(|&index| child[index].signum() != child[index - 1].signum())
+                .collect()
+        }
+
+        mod single_point {
+            use super::*;
+
+            #[test]
+            fn test() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let (parent_a, parent_b) = parents();
+
+                let child = SinglePointCrossover::new()
+                    .crossover(&mut rng, &parent_a, &parent_b);
+
+                assert_eq!(cuts(&child), vec![19]);
+                assert_eq!(child[0], 1.0);
+                assert_eq!(child[99], -100.0);
+            }
+        }
+
+        mod k_point {
+            use super::*;
+
+            #[test]
+            fn test() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let (parent_a, parent_b) = parents();
+
+                let child = KPointCrossover::new(3)
+                    .crossover(&mut rng, &parent_a, &parent_b);
+
+                assert_eq!(cuts(&child), vec![19, 63, 83]);
+
+                // Every gene comes from one of the parents, at its place
+                for (index, gene) in child.iter().enumerate() {
+                    assert_eq!(gene.abs(), (index + 1) as f32);
+                }
+            }
+
+            #[test]
+            fn cut_between_every_gene() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let (parent_a, parent_b) = parents();
+
+                let child = KPointCrossover::new(99)
+                    .crossover(&mut rng, &parent_a, &parent_b);
+
+                assert_eq!(cuts(&child), (1..100).collect::<Vec<_>>());
+            }
+
+            #[test]
+            #[should_panic]
+            fn zero_points() {
+                KPointCrossover::new(0);
+            }
+
+            #[test]
+            #[should_panic(expected = "not enough genes for 100 cut points")]
+            fn too_many_points() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let (parent_a, parent_b) = parents();
+
+                KPointCrossover::new(100).crossover(&mut rng, &parent_a, &parent_b);
+            }
+        }
+
+        mod arithmetic {
+            use super::*;
+
+            #[test]
+            fn test() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let parent_a: Chromosome = vec![1.0, 2.0, -3.0].into_iter().collect();
+                let parent_b: Chromosome = vec![3.0, -2.0, 1.0].into_iter().collect();
+
+                let child = ArithmeticCrossover::new(0.25)
+                    .crossover(&mut rng, &parent_a, &parent_b);
+                let expected: Chromosome = vec![2.5, -1.0, 0.0].into_iter().collect();
+
+                assert_eq!(child, expected);
+            }
+
+            #[test]
+            #[should_panic]
+            fn invalid_weight() {
+                ArithmeticCrossover::new(1.5);
+            }
+        }
+
+        mod blend {
+            use super::*;
+
+            #[test]
+            fn test() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let (parent_a, parent_b) = parents();
+
+                let child = BlendCrossover::new(0.5)
+                    .crossover(&mut rng, &parent_a, &parent_b);
+
+                // The range of the n-th gene is [-2n, 2n], half of which lies outside the parents
+                for (index, gene) in child.iter().enumerate() {
+                    assert!(gene.abs() <= 2.0 * (index + 1) as f32);
+                }
+
+                let outside = child
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, gene)| gene.abs() > (index + 1) as f32)
+                    .count();
+
+                assert_eq!(outside, 40);
+            }
+
+            #[test]
+            fn without_alpha_stays_between_parents() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let (parent_a, parent_b) = parents();
+
+                let child = BlendCrossover::new(0.0)
+                    .crossover(&mut rng, &parent_a, &parent_b);
+
+                for (index, gene) in child.iter().enumerate() {
+                    assert!(gene.abs() <= (index + 1) as f32);
+                }
+            }
+
+            #[test]
+            #[should_panic]
+            fn negative_alpha() {
+                BlendCrossover::new(-0.5);
+            }
+        }
+
+        mod simulated_binary {
+            use super::*;
+
+            /// Average distance of the genes of the child to the nearest parent,
+            /// relative to the distance between the parents.
+            fn spread(eta: f32) -> f32 {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let (parent_a, parent_b) = parents();
+
+                let child = SimulatedBinaryCrossover::new(eta)
+                    .crossover(&mut rng, &parent_a, &parent_b);
+
+                child
+                    .iter()
+                    .enumerate()
+                    .map(|(index, gene)| {
+                        let parent = (index + 1) as f32;
+                        (parent - gene.abs()).abs() / (2.0 * parent)
+                    })
+                    .sum::<f32>() / child.len() as f32
+            }
+
+            #[test]
+            fn test() {
+                approx::assert_relative_eq!(spread(2.0), 0.15328439);
+            }
+
+            #[test]
+            fn higher_eta_stays_closer_to_parents() {
+                assert!(spread(20.0) < spread(2.0));
+                assert!(spread(2.0) < spread(0.0));
+            }
+
+            #[test]
+            fn identical_parents_give_the_same_child() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let (parent, _) = parents();
+
+                let child = SimulatedBinaryCrossover::new(2.0)
+                    .crossover(&mut rng, &parent, &parent);
+
+                assert_eq!(child, parent);
+            }
+
+            #[test]
+            #[should_panic]
+            fn negative_eta() {
+                SimulatedBinaryCrossover::new(-1.0);
+            }
+        }
     }
 
     mod mutation {
@@ -289,7 +926,7 @@ mod tests {
                 #[test]
                 fn slightly_changes_original_chromosome() {
                     let result = mutate_a_child(0.5, 0.5);
-                    let expected = vec![1.0, 1.7756249, 3.0, 4.1596804, 5.0];
+                    let expected = vec![1.0, 2.0, 2.0324764, 3.467692, 4.4987187];
 
                     approx::assert_relative_eq!(
                         result.as_slice(),
@@ -325,11 +962,11 @@ mod tests {
                     let result = mutate_a_child(1.0, 0.5);
 
                     let expected = vec![
-                        1.4545316,
-                        2.1162078,
-                        2.7756248,
-                        3.9505124,
-                        4.638691,
+                        1.6888486,
+                        2.2026734,
+                        2.4018655,
+                        3.0324764,
+                        4.664113,
                     ];
 
                     approx::assert_relative_eq!(
@@ -341,6 +978,332 @@ mod tests {
         }
     }
 
+    mod mutation_distribution {
+        use super::*;
+
+        /// Perturbations of 10 000 genes starting at zero, sorted.
+        fn perturbations(method: &dyn MutationMethod) -> Vec<f32> {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut child: Chromosome = vec![0.0; 10_000].into_iter().collect();
+
+            method.mutate(&mut rng, &mut child);
+
+            let mut genes: Vec<_> = child.into_iter().collect();
+            genes.sort_by(f32::total_cmp);
+            genes
+        }
+
+        fn share(genes: &[f32], predicate: impl Fn(f32) -> bool) -> f32 {
+            genes.iter().filter(|&&gene| predicate(gene)).count() as f32 / genes.len() as f32
+        }
+
+        mod gaussian {
+            use super::*;
+
+            #[test]
+            fn is_normally_distributed() {
+                let genes = perturbations(&GaussianMutation::new(1.0, 2.0));
+
+                let mean = genes.iter().sum::<f32>() / genes.len() as f32;
+                let variance = genes
+                    .iter()
+                    .map(|gene| (gene - mean).powi(2))
+                    .sum::<f32>() / genes.len() as f32;
+
+                approx::assert_abs_diff_eq!(mean, 0.0, epsilon = 0.05);
+                approx::assert_abs_diff_eq!(variance.sqrt(), 2.0, epsilon = 0.05);
+
+                // 68-95-99.7 rule, where a uniform perturbation would give 100% within 2 sigma
+                approx::assert_abs_diff_eq!(share(&genes, |gene| gene.abs() < 2.0), 0.683, epsilon = 0.01);
+                approx::assert_abs_diff_eq!(share(&genes, |gene| gene.abs() < 4.0), 0.954, epsilon = 0.01);
+                approx::assert_abs_diff_eq!(share(&genes, |gene| gene.abs() < 6.0), 0.997, epsilon = 0.003);
+            }
+
+            #[test]
+            fn only_mutates_with_given_chance() {
+                let genes = perturbations(&GaussianMutation::new(0.25, 1.0));
+
+                approx::assert_abs_diff_eq!(share(&genes, |gene| gene == 0.0), 0.75, epsilon = 0.01);
+            }
+
+            #[test]
+            fn bounds() {
+                let genes = perturbations(&GaussianMutation::new(1.0, 1.0).with_bounds(-0.5, 1.0));
+
+                assert_eq!(genes[0], -0.5);
+                assert_eq!(genes[genes.len() - 1], 1.0);
+
+                // P(X < -0.5) and P(X > 1) for a standard normal distribution
+                approx::assert_abs_diff_eq!(share(&genes, |gene| gene == -0.5), 0.309, epsilon = 0.01);
+                approx::assert_abs_diff_eq!(share(&genes, |gene| gene == 1.0), 0.159, epsilon = 0.01);
+            }
+
+            #[test]
+            #[should_panic]
+            fn invalid_bounds() {
+                GaussianMutation::new(1.0, 1.0).with_bounds(1.0, -1.0);
+            }
+        }
+
+        mod cauchy {
+            use super::*;
+
+            #[test]
+            fn is_cauchy_distributed() {
+                let genes = perturbations(&CauchyMutation::new(1.0, 0.5));
+
+                // Half of the perturbations are within the scale, a quarter on each side
+                approx::assert_abs_diff_eq!(share(&genes, |gene| gene.abs() < 0.5), 0.5, epsilon = 0.01);
+                approx::assert_abs_diff_eq!(share(&genes, |gene| gene > 0.5), 0.25, epsilon = 0.01);
+
+                // Heavy tails: P(|X| > 10 * scale) = 1 - 2 * atan(10) / pi
+                approx::assert_abs_diff_eq!(share(&genes, |gene| gene.abs() > 5.0), 0.063, epsilon = 0.01);
+            }
+
+            #[test]
+            #[should_panic]
+            fn invalid_scale() {
+                CauchyMutation::new(1.0, 0.0);
+            }
+        }
+
+        mod self_adaptive {
+            use super::*;
+
+            #[test]
+            fn step_sizes_mutate_log_normally() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let genes = 5_000;
+                let mut child: Chromosome = vec![0.0; genes]
+                    .into_iter()
+                    .chain(vec![1.0; genes])
+                    .collect();
+
+                SelfAdaptiveMutation::new(StepSizes::PerGene).mutate(&mut rng, &mut child);
+
+                let logs: Vec<_> = child.iter().skip(genes).map(|step_size| step_size.ln()).collect();
+                let mean = logs.iter().sum::<f32>() / genes as f32;
+                let variance = logs
+                    .iter()
+                    .map(|log| (log - mean).powi(2))
+                    .sum::<f32>() / genes as f32;
+
+                // The common factor shifts every step size alike, so it barely shows in the variance:
+                // only the individual rate 1 / sqrt(2 * sqrt(5000)) = 0.084 does
+                approx::assert_abs_diff_eq!(mean, 0.0, epsilon = 0.05);
+                approx::assert_abs_diff_eq!(variance.sqrt(), 0.084, epsilon = 0.005);
+
+                // Genes moved by about one step size
+                let deviation = (child.iter().take(genes).map(|gene| gene * gene).sum::<f32>() / genes as f32).sqrt();
+
+                approx::assert_abs_diff_eq!(deviation, 1.0, epsilon = 0.05);
+            }
+
+            #[test]
+            fn genes_move_by_their_own_step_size() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let mut child: Chromosome = vec![0.0; 2_000]
+                    .into_iter()
+                    .chain(vec![0.01; 1_000])
+                    .chain(vec![10.0; 1_000])
+                    .collect();
+
+                // Step sizes mutate by a few percents at most, so genes keep their scale
+                SelfAdaptiveMutation::new(StepSizes::PerGene).mutate(&mut rng, &mut child);
+
+                assert!(child.iter().take(1_000).all(|gene| gene.abs() < 0.1));
+                assert!(child.iter().skip(1_000).take(1_000).any(|gene| gene.abs() > 10.0));
+            }
+
+            #[test]
+            fn single_step_size() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let mut child: Chromosome = vec![1.0, 2.0, 3.0, 0.5].into_iter().collect();
+
+                SelfAdaptiveMutation::new(StepSizes::Single).mutate(&mut rng, &mut child);
+
+                let actual: Vec<_> = child.into_iter().collect();
+                let expected = vec![0.24415022, 0.77736115, 2.575547, 0.63183933];
+
+                approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+            }
+
+            #[test]
+            fn step_sizes_never_vanish() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let mut child: Chromosome = vec![0.0, 0.0, 0.0, 0.0].into_iter().collect();
+                let method = SelfAdaptiveMutation::new(StepSizes::PerGene).with_min_step_size(0.1);
+
+                method.mutate(&mut rng, &mut child);
+
+                assert_eq!(child[2], 0.1);
+                assert_eq!(child[3], 0.1);
+            }
+
+            #[test]
+            fn count() {
+                assert_eq!(StepSizes::Single.count(10), 1);
+                assert_eq!(StepSizes::PerGene.count(10), 10);
+                assert_eq!(StepSizes::Single.genes(11), 10);
+                assert_eq!(StepSizes::PerGene.genes(20), 10);
+            }
+
+            #[test]
+            #[should_panic(expected = "a chromosome with a step size per gene has an even length")]
+            fn odd_chromosome_with_a_step_size_per_gene() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let mut child: Chromosome = vec![0.0, 0.0, 0.1].into_iter().collect();
+
+                SelfAdaptiveMutation::new(StepSizes::PerGene).mutate(&mut rng, &mut child);
+            }
+
+            #[test]
+            #[should_panic(expected = "a chromosome with a single step size has at least one gene")]
+            fn empty_chromosome_with_a_single_step_size() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let mut child: Chromosome = Vec::new().into_iter().collect();
+
+                SelfAdaptiveMutation::new(StepSizes::Single).mutate(&mut rng, &mut child);
+            }
+        }
+
+        mod polynomial {
+            use super::*;
+
+            #[test]
+            fn stays_within_bounds() {
+                let genes = perturbations(&PolynomialMutation::new(1.0, 1.0, -1.0, 2.0));
+
+                assert!(genes[0] >= -1.0);
+                assert!(genes[genes.len() - 1] <= 2.0);
+
+                // The gene can move further up than down, but both ways are equally likely
+                approx::assert_abs_diff_eq!(share(&genes, |gene| gene < 0.0), 0.5, epsilon = 0.02);
+            }
+
+            #[test]
+            fn higher_eta_gives_smaller_changes() {
+                let mean_change = |eta| {
+                    let genes = perturbations(&PolynomialMutation::new(1.0, eta, -1.0, 1.0));
+                    genes.iter().map(|gene| gene.abs()).sum::<f32>() / genes.len() as f32
+                };
+
+                // Far enough from the bounds, the mean change is range / (eta + 2)
+                approx::assert_abs_diff_eq!(mean_change(20.0), 2.0 / 22.0, epsilon = 0.002);
+                approx::assert_abs_diff_eq!(mean_change(100.0), 2.0 / 102.0, epsilon = 0.001);
+                assert!(mean_change(0.0) > mean_change(20.0));
+            }
+
+            #[test]
+            #[should_panic]
+            fn invalid_bounds() {
+                PolynomialMutation::new(1.0, 20.0, 1.0, 1.0);
+            }
+        }
+    }
+
+    mod statistics {
+        use super::*;
+
+        fn population(genes: &[[f32; 2]]) -> Vec<TestIndividual> {
+            genes
+                .iter()
+                .map(|genes| TestIndividual::create(genes.iter().copied().collect()))
+                .collect()
+        }
+
+        fn statistics() -> Statistics {
+            // Fitnesses of 0, 2, 4 and 8
+            Statistics::new(&population(&[[1.0, 1.0], [3.0, 5.0], [0.0, 0.0], [2.0, 2.0]]), 2)
+        }
+
+        #[test]
+        fn fitness() {
+            let stats = statistics();
+
+            approx::assert_relative_eq!(stats.min_fitness(), 0.0);
+            approx::assert_relative_eq!(stats.max_fitness(), 8.0);
+            approx::assert_relative_eq!(stats.avg_fitness(), 3.5);
+            approx::assert_relative_eq!(stats.median_fitness(), 3.0);
+            approx::assert_relative_eq!(stats.std_dev_fitness(), 8.75f32.sqrt());
+        }
+
+        #[test]
+        fn percentiles() {
+            let stats = statistics();
+
+            approx::assert_relative_eq!(stats.percentile_fitness(0.0), 0.0);
+            approx::assert_relative_eq!(stats.percentile_fitness(25.0), 1.5);
+            approx::assert_relative_eq!(stats.percentile_fitness(50.0), stats.median_fitness());
+            approx::assert_relative_eq!(stats.percentile_fitness(90.0), 6.8, epsilon = 1e-5);
+            approx::assert_relative_eq!(stats.percentile_fitness(100.0), 8.0);
+        }
+
+        #[test]
+        #[should_panic]
+        fn invalid_percentile() {
+            statistics().percentile_fitness(101.0);
+        }
+
+        #[test]
+        fn histogram() {
+            let stats = statistics();
+
+            assert_eq!(stats.fitness_histogram(1), vec![4]);
+            assert_eq!(stats.fitness_histogram(2), vec![2, 2]);
+            assert_eq!(stats.fitness_histogram(4), vec![1, 1, 1, 1]);
+            assert_eq!(stats.fitness_histogram(8), vec![1, 0, 1, 0, 1, 0, 0, 1]);
+        }
+
+        #[test]
+        fn diversity() {
+            let stats = statistics();
+
+            let actual = stats.gene_variances().to_vec();
+            let expected = vec![1.25, 3.5];
+            approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+
+            approx::assert_relative_eq!(stats.mean_gene_variance(), 2.375);
+
+            // Distances of sqrt(2), sqrt(8), sqrt(34), sqrt(2), sqrt(20) and sqrt(10)
+            approx::assert_relative_eq!(stats.mean_pairwise_distance(), 3.187034, epsilon = 1e-5);
+        }
+
+        #[test]
+        fn step_size_genes_are_left_out() {
+            // Same genes as `statistics()`, each followed by a step size
+            let population: Vec<_> = [[1.0, 1.0, 0.1], [3.0, 5.0, 0.9], [0.0, 0.0, 0.5], [2.0, 2.0, 0.01]]
+                .iter()
+                .map(|genes| TestIndividual::create(genes.iter().copied().collect()))
+                .collect();
+            let stats = Statistics::new(&population, StepSizes::Single.genes(3));
+
+            let actual = stats.gene_variances().to_vec();
+            let expected = vec![1.25, 3.5];
+            approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+            approx::assert_relative_eq!(stats.mean_pairwise_distance(), 3.187034, epsilon = 1e-5);
+        }
+
+        #[test]
+        fn converged_population() {
+            let stats = Statistics::new(&population(&[[1.0, 2.0]; 3]), 2);
+
+            approx::assert_relative_eq!(stats.std_dev_fitness(), 0.0);
+            approx::assert_relative_eq!(stats.mean_gene_variance(), 0.0);
+            approx::assert_relative_eq!(stats.mean_pairwise_distance(), 0.0);
+            assert_eq!(stats.fitness_histogram(3), vec![3, 0, 0]);
+        }
+
+        #[test]
+        fn single_individual() {
+            let stats = Statistics::new(&population(&[[1.0, 2.0]]), 2);
+
+            approx::assert_relative_eq!(stats.median_fitness(), 3.0);
+            approx::assert_relative_eq!(stats.percentile_fitness(10.0), 3.0);
+            approx::assert_relative_eq!(stats.mean_pairwise_distance(), 0.0);
+        }
+    }
+
     mod evolution {
         use super::*;
 
@@ -376,10 +1339,10 @@ mod tests {
             }
 
             let expected_population = vec![
-                individual(&[0.44769490, 2.0648358, 4.3058133]),
-                individual(&[1.21268670, 1.5538777, 2.8869110]),
-                individual(&[1.06176780, 2.2657390, 4.4287640]),
-                individual(&[0.95909685, 2.4618788, 4.0247330]),
+                individual(&[0.9448564, 1.9164591, 1.8737841]),
+                individual(&[0.30641347, 0.47526756, 1.8737841]),
+                individual(&[0.68339884, 1.2574067, 2.410377]),
+                individual(&[1.1643567, 1.2574067, 2.6468039]),
             ];
 
             assert_eq!(population, expected_population);
@@ -391,5 +1354,92 @@ mod tests {
 
             assert!(sum_fitness_initial < sum_fitness_population)
         }
+
+        #[test]
+        fn statistics_leave_out_step_sizes() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let genetic_algorithm = GeneticAlgorithm::new(
+                RouletteWheelSelection::new(),
+                UniformCrossover::new(),
+                SelfAdaptiveMutation::new(StepSizes::PerGene)
+            );
+
+            // Same genes, but step sizes that are far apart
+            let population = vec![
+                individual(&[1.0, 2.0, 0.1, 0.1]),
+                individual(&[1.0, 2.0, 5.0, 5.0]),
+            ];
+
+            let (_, stats) = genetic_algorithm.evolve(&mut rng, &population);
+
+            assert_eq!(stats.gene_variances(), &[0.0, 0.0]);
+            approx::assert_relative_eq!(stats.mean_pairwise_distance(), 0.0);
+        }
+
+        #[test]
+        fn elitism() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let genetic_algorithm = GeneticAlgorithm::new(
+                RouletteWheelSelection::new(),
+                UniformCrossover::new(),
+                GaussianMutation::new(1.0, 0.5)
+            )
+            .with_elitism(2);
+
+            let population = vec![
+                individual(&[0.0, 0.0, 0.0]), // fitness = 0.0
+                individual(&[1.0, 2.0, 4.0]), // fitness = 7.0
+                individual(&[1.0, 1.0, 1.0]), // fitness = 3.0
+                individual(&[1.0, 2.0, 1.0]), // fitness = 4.0
+            ];
+
+            let (new_population, stats) = genetic_algorithm.evolve(&mut rng, &population);
+
+            assert_eq!(new_population.len(), 4);
+            assert_eq!(new_population[0], population[1]);
+            assert_eq!(new_population[1], population[3]);
+            assert_eq!(stats.elites(), 2);
+            assert!(stats.elite_retained());
+
+            // Every other chromosome goes through mutation
+            assert!(new_population[2..].iter().all(|child| !population.contains(child)));
+        }
+
+        #[test]
+        fn without_elitism_elite_is_not_retained() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let genetic_algorithm = GeneticAlgorithm::new(
+                RouletteWheelSelection::new(),
+                UniformCrossover::new(),
+                GaussianMutation::new(1.0, 0.5)
+            );
+
+            let population = vec![individual(&[1.0, 2.0, 4.0]), individual(&[0.0, 0.0, 0.0])];
+            let (_, stats) = genetic_algorithm.evolve(&mut rng, &population);
+
+            assert_eq!(stats.elites(), 0);
+            assert!(!stats.elite_retained());
+        }
+
+        #[test]
+        fn without_elitism_elite_can_be_retained_by_chance() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            // Only the fittest individual can be selected, and nothing mutates
+            let genetic_algorithm = GeneticAlgorithm::new(
+                RouletteWheelSelection::new(),
+                UniformCrossover::new(),
+                GaussianMutation::new(0.0, 0.5)
+            );
+
+            let population = vec![individual(&[1.0, 2.0, 4.0]), individual(&[0.0, 0.0, 0.0])];
+            let (_, stats) = genetic_algorithm.evolve(&mut rng, &population);
+
+            assert_eq!(stats.elites(), 0);
+            assert!(stats.elite_retained());
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,56 @@
+use crate::*;
+
+/// A function applied to the weighted sum of a neuron's inputs.
+/// Defaults to ReLU, to stay consistent with the behaviour of older networks.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Activation {
+    /// `max(0, x)`
+    #[default]
+    ReLU,
+    /// `x` if `x > 0`, `slope * x` otherwise
+    LeakyReLU(f32),
+    /// `1 / (1 + e^-x)`, in `]0, 1[`
+    Sigmoid,
+    /// Hyperbolic tangent, in `]-1, 1[`
+    Tanh,
+    /// `x`, leaves the value untouched
+    Identity,
+    /// `x / (1 + |x|)`, in `]-1, 1[`
+    Softsign,
+    /// `1` if `x > 0`, `0` otherwise
+    Step,
+}
+
+impl Activation {
+    /// Applies the activation function to `x`.
+    pub fn apply<S: Scalar>(&self, x: S) -> S {
+        match *self {
+            Self::ReLU => if x > S::ZERO { x } else { S::ZERO },
+            Self::LeakyReLU(slope) => if x > S::ZERO { x } else { S::from_f32(slope) * x },
+            Self::Sigmoid => S::ONE / (S::ONE + (-x).exp()),
+            Self::Tanh => x.tanh(),
+            Self::Identity => x,
+            Self::Softsign => x / (S::ONE + x.abs()),
+            Self::Step => if x > S::ZERO { S::ONE } else { S::ZERO },
+        }
+    }
+
+    /// Derivative of the activation function at `x`, used by backpropagation.
+    /// `Step` is flat everywhere it is differentiable, so it lets no gradient through.
+    pub fn derivative(&self, x: f32) -> f32 {
+        match *self {
+            Self::ReLU => if x > 0.0 { 1.0 } else { 0.0 },
+            Self::LeakyReLU(slope) => if x > 0.0 { 1.0 } else { slope },
+            Self::Sigmoid => {
+                let y = self.apply(x);
+                y * (1.0 - y)
+            }
+            Self::Tanh => 1.0 - x.tanh().powi(2),
+            Self::Identity => 1.0,
+            Self::Softsign => 1.0 / (1.0 + x.abs()).powi(2),
+            Self::Step => 0.0,
+        }
+    }
+}
+
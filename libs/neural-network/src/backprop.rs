@@ -0,0 +1,277 @@
+//! Supervised training of networks by backpropagation.
+//!
+//! Gradients are laid out like `Network::weights()`, so that a network trained this way
+//! can still be turned into a chromosome, and the other way around.
+//! Recurrent layers are trained with truncated backpropagation of a single step:
+//! their previous outputs are treated as plain inputs.
+
+use crate::*;
+
+/// Mean squared error between `outputs` and `targets`.
+pub fn mse(outputs: &[f32], targets: &[f32]) -> f32 {
+    assert_eq!(outputs.len(), targets.len());
+
+    let sum = outputs
+        .iter()
+        .zip(targets)
+        .map(|(output, target)| (output - target).powi(2))
+        .sum::<f32>();
+
+    sum / outputs.len() as f32
+}
+
+/// Gradient of the loss with respect to every weight of a network,
+/// in the same order as `Network::weights()`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gradients {
+    values: Vec<f32>,
+}
+
+impl Gradients {
+    pub fn values(&self) -> &[f32] {
+        &self.values
+    }
+}
+
+/// Moves the weights of a network against the gradient of its loss.
+pub trait Optimizer {
+    /// Updates `weights`, laid out like `Network::weights()`, given their `gradients`.
+    fn step(&mut self, weights: &mut [f32], gradients: &Gradients);
+}
+
+/// Stochastic gradient descent: each weight moves by `-learning_rate * gradient`.
+#[derive(Clone, Debug)]
+pub struct Sgd {
+    learning_rate: f32,
+}
+
+impl Sgd {
+    pub fn new(learning_rate: f32) -> Self {
+        Self { learning_rate }
+    }
+}
+
+impl Optimizer for Sgd {
+    fn step(&mut self, weights: &mut [f32], gradients: &Gradients) {
+        assert_eq!(weights.len(), gradients.values.len());
+
+        for (weight, gradient) in weights.iter_mut().zip(&gradients.values) {
+            *weight -= self.learning_rate * gradient;
+        }
+    }
+}
+
+/// Adam: gradient descent with per-weight step sizes,
+/// adapted from running averages of the gradients and of their squares.
+#[derive(Clone, Debug)]
+pub struct Adam {
+    learning_rate: f32,
+    beta1: f32,
+    beta2: f32,
+    epsilon: f32,
+    /// Number of steps taken so far, to correct the bias of the averages
+    steps: i32,
+    moments: Vec<f32>,
+    velocities: Vec<f32>,
+}
+
+impl Adam {
+    /// Initializes the optimizer with the usual decay rates (0.9 and 0.999).
+    pub fn new(learning_rate: f32) -> Self {
+        Self::with_betas(learning_rate, 0.9, 0.999)
+    }
+
+    pub fn with_betas(learning_rate: f32, beta1: f32, beta2: f32) -> Self {
+        assert!((0.0..1.0).contains(&beta1));
+        assert!((0.0..1.0).contains(&beta2));
+
+        Self {
+            learning_rate,
+            beta1,
+            beta2,
+            epsilon: 1e-8,
+            steps: 0,
+            moments: Vec::new(),
+            velocities: Vec::new(),
+        }
+    }
+}
+
+impl Optimizer for Adam {
+    fn step(&mut self, weights: &mut [f32], gradients: &Gradients) {
+        assert_eq!(weights.len(), gradients.values.len());
+
+        // The averages start at zero, the first time the optimizer sees this network
+        self.moments.resize(weights.len(), 0.0);
+        self.velocities.resize(weights.len(), 0.0);
+        self.steps += 1;
+
+        let moment_correction = 1.0 - self.beta1.powi(self.steps);
+        let velocity_correction = 1.0 - self.beta2.powi(self.steps);
+
+        let params = weights
+            .iter_mut()
+            .zip(&gradients.values)
+            .zip(self.moments.iter_mut().zip(&mut self.velocities));
+
+        for ((weight, &gradient), (moment, velocity)) in params {
+            *moment = self.beta1 * *moment + (1.0 - self.beta1) * gradient;
+            *velocity = self.beta2 * *velocity + (1.0 - self.beta2) * gradient * gradient;
+
+            let moment = *moment / moment_correction;
+            let velocity = *velocity / velocity_correction;
+
+            *weight -= self.learning_rate * moment / (velocity.sqrt() + self.epsilon);
+        }
+    }
+}
+
+impl Network {
+    /// Panicking version of `try_backpropagate`.
+    pub fn backpropagate(&mut self, inputs: &[f32], targets: &[f32]) -> (f32, Gradients) {
+        self.try_backpropagate(inputs, targets).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Propagates `inputs`, then returns the mean squared error between the outputs
+    /// and `targets`, along with its gradient with respect to every weight.
+    /// Recurrent layers move on to their next state, just like with a regular propagation.
+    pub fn try_backpropagate(
+        &mut self,
+        inputs: &[f32],
+        targets: &[f32]
+    ) -> Result<(f32, Gradients), NetworkError> {
+        self.check_inputs(inputs)?;
+
+        if targets.len() != self.output_size() {
+            return Err(NetworkError::TargetSizeMismatch {
+                expected: self.output_size(),
+                actual: targets.len()
+            });
+        }
+
+        // What the recurrent layers remembered before this propagation
+        let states: Vec<_> = self.layers.iter().map(|layer| layer.state().to_vec()).collect();
+        let trace = self.try_propagate_traced(inputs)?;
+        let outputs = trace.outputs();
+
+        // Each neuron holds its bias, then its input weights, then its recurrent weights
+        let strides: Vec<_> = self.layers
+            .iter()
+            .zip(&states)
+            .map(|(layer, state)| 1 + layer.input_size() + state.len())
+            .collect();
+
+        let mut offsets = Vec::with_capacity(self.layers.len());
+        let mut values = Vec::new();
+
+        for (layer, stride) in self.layers.iter().zip(&strides) {
+            offsets.push(values.len());
+            values.resize(values.len() + layer.output_size() * stride, 0.0);
+        }
+
+        // Gradient of the loss with respect to the outputs of the current layer
+        let mut deltas: Vec<_> = outputs
+            .iter()
+            .zip(targets)
+            .map(|(output, target)| 2.0 * (output - target) / outputs.len() as f32)
+            .collect();
+
+        for (index, layer) in self.layers.iter().enumerate().rev() {
+            let inputs = match index {
+                0 => inputs,
+                _ => &trace.layers[index - 1].activations,
+            };
+            let pre_activations = &trace.layers[index].pre_activations;
+            let mut previous_deltas = vec![0.0; layer.input_size()];
+
+            for neuron in 0..layer.output_size() {
+                let delta = deltas[neuron] * layer.activation().derivative(pre_activations[neuron]);
+                let start = offsets[index] + neuron * strides[index];
+                let gradients = &mut values[start..start + strides[index]];
+
+                gradients[0] = delta;
+
+                for (gradient, input) in gradients[1..].iter_mut().zip(inputs.iter().chain(&states[index])) {
+                    *gradient = delta * input;
+                }
+
+                for (previous, weight) in previous_deltas.iter_mut().zip(layer.row(neuron)) {
+                    *previous += delta * weight;
+                }
+            }
+
+            deltas = previous_deltas;
+        }
+
+        Ok((mse(outputs, targets), Gradients { values }))
+    }
+
+    /// Panicking version of `try_train_batch`.
+    pub fn train_batch<'a>(
+        &mut self,
+        optimizer: &mut dyn Optimizer,
+        samples: impl IntoIterator<Item = (&'a [f32], &'a [f32])>
+    ) -> f32 {
+        self.try_train_batch(optimizer, samples).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Backpropagates each `(inputs, targets)` sample in order,
+    /// then takes one optimizer step along the average of their gradients.
+    /// Returns the average loss of the samples, before the step.
+    pub fn try_train_batch<'a>(
+        &mut self,
+        optimizer: &mut dyn Optimizer,
+        samples: impl IntoIterator<Item = (&'a [f32], &'a [f32])>
+    ) -> Result<f32, NetworkError> {
+        let mut weights = self.weights();
+        let mut sum = vec![0.0; weights.len()];
+        let mut loss = 0.0;
+        let mut count = 0;
+
+        for (inputs, targets) in samples {
+            let (sample_loss, gradients) = self.try_backpropagate(inputs, targets)?;
+
+            for (sum, gradient) in sum.iter_mut().zip(gradients.values) {
+                *sum += gradient;
+            }
+
+            loss += sample_loss;
+            count += 1;
+        }
+
+        if count == 0 {
+            return Ok(0.0);
+        }
+
+        let gradients = Gradients {
+            values: sum.into_iter().map(|gradient| gradient / count as f32).collect(),
+        };
+
+        optimizer.step(&mut weights, &gradients);
+        self.set_weights(&weights);
+
+        Ok(loss / count as f32)
+    }
+
+    /// Overwrites every weight of the network, given in the order of `weights()`.
+    fn set_weights(&mut self, weights: &[f32]) {
+        let mut weights = weights.iter().copied();
+
+        for layer in &mut self.layers {
+            let input_size = layer.input_size;
+            let recurrent_size = layer.state.len();
+
+            for neuron in 0..layer.biases.len() {
+                layer.biases[neuron] = weights.next().unwrap();
+
+                let row = &mut layer.weights[neuron * input_size..(neuron + 1) * input_size];
+                let recurrent_row =
+                    &mut layer.recurrent_weights[neuron * recurrent_size..(neuron + 1) * recurrent_size];
+
+                for weight in row.iter_mut().chain(recurrent_row) {
+                    *weight = weights.next().unwrap();
+                }
+            }
+        }
+    }
+}
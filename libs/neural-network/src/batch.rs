@@ -0,0 +1,221 @@
+use crate::*;
+
+/// Many networks sharing the same topology, stored as a structure of arrays
+/// so that all of them can be propagated at once.
+/// For each weight, the values of every network are contiguous,
+/// which keeps the innermost loop linear in memory.
+#[derive(Debug)]
+pub struct NetworkBatch {
+    size: usize,
+    layers: Vec<LayerBatch>,
+}
+
+impl NetworkBatch {
+    /// Interleaves the weights of `networks`, which must all have the same topology.
+    /// Without any network, the batch is empty and propagates nothing.
+    pub fn new<'a>(networks: impl IntoIterator<Item = &'a Network>) -> Self {
+        let networks: Vec<_> = networks.into_iter().collect();
+
+        let layers = networks.first().map_or_else(Vec::new, |first| {
+            (0..first.layers.len())
+                .map(|index| {
+                    LayerBatch::new(networks.iter().map(|network| &network.layers[index]))
+                })
+                .collect()
+        });
+
+        Self { size: networks.len(), layers }
+    }
+
+    /// Number of networks in the batch
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    /// Whether the batch holds no network
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Computes the output of every network of the batch.
+    /// `inputs` is a row-major matrix holding one row of inputs per network,
+    /// and so is the returned matrix of outputs.
+    pub fn propagate(&mut self, inputs: &[f32]) -> Vec<f32> {
+        let mut workspace = Workspace::new();
+        self.propagate_into(inputs, &mut workspace);
+
+        workspace.front
+    }
+
+    /// Same as `propagate`, but using the buffers of `workspace`.
+    pub fn propagate_into<'a>(
+        &mut self,
+        inputs: &[f32],
+        workspace: &'a mut Workspace
+    ) -> &'a [f32] {
+        let Workspace { front, back } = workspace;
+
+        if self.is_empty() {
+            front.clear();
+            return front;
+        }
+
+        // Interleaves the inputs: one row per input, one column per network
+        transpose(inputs, self.size, front);
+
+        for layer in &mut self.layers {
+            layer.propagate_into(self.size, front, back);
+            std::mem::swap(front, back);
+        }
+
+        // Back to one row per network
+        transpose(front, self.layers[self.layers.len() - 1].output_size, back);
+        std::mem::swap(front, back);
+
+        front
+    }
+
+    /// Forgets what the recurrent layers of every network remember.
+    pub fn reset_state(&mut self) {
+        for layer in &mut self.layers {
+            layer.state.fill(0.0);
+        }
+    }
+
+    /// Copies the state of the recurrent layers of every network back into `networks`,
+    /// which must be the networks of the batch, in the same order.
+    pub fn store_state<'a>(&self, networks: impl IntoIterator<Item = &'a mut Network>) {
+        let mut count = 0;
+
+        for (index, network) in networks.into_iter().enumerate() {
+            for (layer, batch) in network.layers.iter_mut().zip(&self.layers) {
+                for (neuron, state) in layer.state.iter_mut().enumerate() {
+                    *state = batch.state[neuron * self.size + index];
+                }
+            }
+
+            count += 1;
+        }
+
+        assert_eq!(count, self.size);
+    }
+}
+
+/// The layers of same index of every network in a `NetworkBatch`.
+#[derive(Debug)]
+struct LayerBatch {
+    input_size: usize,
+    output_size: usize,
+    /// Weight `i` of neuron `o` of network `n` is at `(o * input_size + i) * size + n`.
+    weights: Vec<f32>,
+    /// Bias of neuron `o` of network `n` is at `o * size + n`.
+    biases: Vec<f32>,
+    activation: Activation,
+    kind: LayerKind,
+    /// Recurrent weight `j` of neuron `o` of network `n` is at `(o * output_size + j) * size + n`.
+    recurrent_weights: Vec<f32>,
+    /// Previous output of neuron `o` of network `n` is at `o * size + n`.
+    state: Vec<f32>,
+}
+
+impl LayerBatch {
+    fn new<'a>(layers: impl Iterator<Item = &'a Layer>) -> Self {
+        let layers: Vec<_> = layers.collect();
+        let first = layers[0];
+
+        for layer in &layers {
+            assert_eq!(layer.input_size(), first.input_size());
+            assert_eq!(layer.output_size(), first.output_size());
+            assert_eq!(layer.activation(), first.activation());
+            assert_eq!(layer.kind(), first.kind());
+        }
+
+        let mut weights = Vec::new();
+        transpose_columns(layers.iter().map(|layer| layer.weights()), &mut weights);
+
+        let mut biases = Vec::new();
+        transpose_columns(layers.iter().map(|layer| layer.biases()), &mut biases);
+
+        let mut recurrent_weights = Vec::new();
+        transpose_columns(
+            layers.iter().map(|layer| layer.recurrent_weights()),
+            &mut recurrent_weights
+        );
+
+        // Each network carries on from its own state
+        let mut state = Vec::new();
+        transpose_columns(layers.iter().map(|layer| layer.state()), &mut state);
+
+        Self {
+            input_size: first.input_size(),
+            output_size: first.output_size(),
+            weights,
+            biases,
+            activation: first.activation(),
+            kind: first.kind(),
+            recurrent_weights,
+            state,
+        }
+    }
+
+    /// Propagates interleaved inputs (one row per input, one column per network)
+    /// into interleaved outputs (one row per neuron, one column per network).
+    fn propagate_into(&mut self, size: usize, inputs: &[f32], outputs: &mut Vec<f32>) {
+        assert_eq!(inputs.len(), self.input_size * size);
+
+        outputs.clear();
+        outputs.resize(self.output_size * size, 0.0);
+
+        let neurons = outputs
+            .chunks_exact_mut(size)
+            .zip(self.weights.chunks_exact(self.input_size * size))
+            .zip(self.biases.chunks_exact(size))
+            .enumerate();
+
+        for (neuron, ((outputs, weights), biases)) in neurons {
+            for (inputs, weights) in inputs.chunks_exact(size).zip(weights.chunks_exact(size)) {
+                for ((output, input), weight) in outputs.iter_mut().zip(inputs).zip(weights) {
+                    *output += input * weight;
+                }
+            }
+
+            if self.kind == LayerKind::Recurrent {
+                let stride = self.output_size * size;
+                let weights = &self.recurrent_weights[neuron * stride..(neuron + 1) * stride];
+
+                for (states, weights) in self.state.chunks_exact(size).zip(weights.chunks_exact(size)) {
+                    for ((output, state), weight) in outputs.iter_mut().zip(states).zip(weights) {
+                        *output += state * weight;
+                    }
+                }
+            }
+
+            for (output, bias) in outputs.iter_mut().zip(biases) {
+                *output = self.activation.apply(*output + bias);
+            }
+        }
+
+        let len = self.state.len();
+        self.state.copy_from_slice(&outputs[..len]);
+    }
+}
+
+/// Transposes a row-major matrix of `rows` rows into `outputs`.
+fn transpose(matrix: &[f32], rows: usize, outputs: &mut Vec<f32>) {
+    assert_eq!(matrix.len() % rows, 0);
+
+    outputs.clear();
+    transpose_columns(matrix.chunks_exact(matrix.len() / rows), outputs);
+}
+
+/// Writes the slices of `columns`, all of the same length, as the columns of a row-major matrix.
+fn transpose_columns<'a>(
+    columns: impl Iterator<Item = &'a [f32]> + Clone,
+    outputs: &mut Vec<f32>
+) {
+    let len = columns.clone().next().map_or(0, |column| column.len());
+
+    for index in 0..len {
+        outputs.extend(columns.clone().map(|column| column[index]));
+    }
+}
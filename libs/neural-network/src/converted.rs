@@ -0,0 +1,55 @@
+//! What the networks converted from a `Network` (sparse, quantized) share:
+//! they propagate `f32` values through layers of their own, which may be recurrent.
+
+use crate::*;
+
+/// A layer of a converted network.
+pub(crate) trait ConvertedLayer {
+    /// Number of inputs of each neuron
+    fn input_size(&self) -> usize;
+
+    /// Outputs of the previous propagation, empty if the layer is dense
+    fn state_mut(&mut self) -> &mut [f32];
+
+    /// Computes the activations of the neurons, given `inputs` and the current state.
+    fn activations(&self, inputs: &[f32]) -> Vec<f32>;
+
+    /// Computes the activations of the neurons, then remembers them if the layer is recurrent.
+    fn propagate(&mut self, inputs: &[f32]) -> Vec<f32> {
+        let outputs = self.activations(inputs);
+
+        let state = self.state_mut();
+        let len = state.len();
+        state.copy_from_slice(&outputs[..len]);
+
+        outputs
+    }
+}
+
+/// Propagates `inputs` through `layers`, once checked that the first layer expects as many.
+pub(crate) fn try_propagate<L: ConvertedLayer>(
+    layers: &mut [L],
+    inputs: &[f32]
+) -> Result<Vec<f32>, NetworkError> {
+    if inputs.len() != layers[0].input_size() {
+        return Err(NetworkError::InputSizeMismatch {
+            expected: layers[0].input_size(),
+            actual: inputs.len()
+        });
+    }
+
+    let mut outputs = layers[0].propagate(inputs);
+
+    for layer in &mut layers[1..] {
+        outputs = layer.propagate(&outputs);
+    }
+
+    Ok(outputs)
+}
+
+/// Forgets what the recurrent layers among `layers` remember.
+pub(crate) fn reset_state<L: ConvertedLayer>(layers: &mut [L]) {
+    for layer in layers {
+        layer.state_mut().fill(0.0);
+    }
+}
@@ -0,0 +1,244 @@
+//! Renders networks as diagrams, one node per neuron and one edge per non-zero weight.
+//! Positive weights are drawn in blue and negative ones in red,
+//! thicker as their magnitude grows; recurrent weights are dashed.
+
+use crate::*;
+use std::fmt::Write;
+
+const POSITIVE: &str = "#2b6cb0";
+const NEGATIVE: &str = "#c53030";
+
+/// Names shown next to the inputs and outputs of a network.
+/// Missing names default to the index of the neuron.
+#[derive(Clone, Debug, Default)]
+pub struct Labels {
+    pub inputs: Vec<String>,
+    pub outputs: Vec<String>,
+}
+
+impl Labels {
+    pub fn new(
+        inputs: impl IntoIterator<Item = impl Into<String>>,
+        outputs: impl IntoIterator<Item = impl Into<String>>
+    ) -> Self {
+        Self {
+            inputs: inputs.into_iter().map(Into::into).collect(),
+            outputs: outputs.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    fn input(&self, neuron: usize) -> String {
+        self.inputs.get(neuron).cloned().unwrap_or_else(|| format!("in {}", neuron))
+    }
+
+    fn output(&self, neuron: usize) -> String {
+        self.outputs.get(neuron).cloned().unwrap_or_else(|| format!("out {}", neuron))
+    }
+}
+
+/// One non-zero weight, going from neuron `from` of layer `layer - 1`
+/// (or of `layer` itself, when recurrent) to neuron `to` of layer `layer`.
+struct Edge {
+    layer: usize,
+    from: usize,
+    to: usize,
+    weight: f32,
+    recurrent: bool,
+}
+
+impl Network {
+    /// Renders the network as a Graphviz DOT graph.
+    pub fn to_dot(&self, labels: &Labels) -> String {
+        let max = self.max_weight();
+        let mut dot = String::new();
+
+        writeln!(dot, "digraph network {{").unwrap();
+        writeln!(dot, "    rankdir=LR;").unwrap();
+        writeln!(dot, "    splines=line;").unwrap();
+        writeln!(dot, "    node [shape=circle, label=\"\", width=0.3];").unwrap();
+
+        for (layer, size) in self.layer_sizes().enumerate() {
+            writeln!(dot, "    {{ rank=same;").unwrap();
+
+            for neuron in 0..size {
+                let label = if layer == 0 {
+                    format!(", label=\"{}\", shape=box", escape_dot(&labels.input(neuron)))
+                } else if layer == self.layers.len() {
+                    format!(", label=\"{}\", shape=box", escape_dot(&labels.output(neuron)))
+                } else {
+                    String::new()
+                };
+
+                let tooltip = match layer {
+                    0 => String::new(),
+                    _ => format!("bias {:.3}", self.layers[layer - 1].biases()[neuron]),
+                };
+
+                writeln!(dot, "        n{}_{} [tooltip=\"{}\"{}];", layer, neuron, tooltip, label).unwrap();
+            }
+
+            writeln!(dot, "    }}").unwrap();
+        }
+
+        for edge in self.edges() {
+            let from_layer = if edge.recurrent { edge.layer } else { edge.layer - 1 };
+
+            writeln!(
+                dot,
+                "    n{}_{} -> n{}_{} [color=\"{}\", penwidth={:.2}, tooltip=\"{:.3}\"{}];",
+                from_layer,
+                edge.from,
+                edge.layer,
+                edge.to,
+                color(edge.weight),
+                stroke_width(edge.weight, max),
+                edge.weight,
+                if edge.recurrent { ", style=dashed, constraint=false" } else { "" }
+            ).unwrap();
+        }
+
+        writeln!(dot, "}}").unwrap();
+
+        dot
+    }
+
+    /// Renders the network as a standalone SVG image, the inputs on the left.
+    pub fn to_svg(&self, labels: &Labels) -> String {
+        const MARGIN: f32 = 100.0;
+        const COLUMN: f32 = 160.0;
+        const ROW: f32 = 36.0;
+        const RADIUS: f32 = 10.0;
+
+        let sizes: Vec<_> = self.layer_sizes().collect();
+        let rows = sizes.iter().copied().max().unwrap_or(0) as f32;
+        let width = 2.0 * MARGIN + (sizes.len() - 1) as f32 * COLUMN;
+        let height = 2.0 * ROW + (rows - 1.0) * ROW;
+
+        // Each layer is centered vertically
+        let position = |layer: usize, neuron: usize| {
+            let top = (height - (sizes[layer] - 1) as f32 * ROW) / 2.0;
+
+            (MARGIN + layer as f32 * COLUMN, top + neuron as f32 * ROW)
+        };
+
+        let max = self.max_weight();
+        let mut svg = String::new();
+
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif" font-size="12">"#,
+            w = width,
+            h = height
+        ).unwrap();
+        writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#).unwrap();
+
+        for edge in self.edges() {
+            let (x2, y2) = position(edge.layer, edge.to);
+
+            let path = if edge.recurrent {
+                // Loops back into the layer, bulging to the right
+                let (x1, y1) = position(edge.layer, edge.from);
+                let bulge = COLUMN / 4.0 + (y2 - y1).abs() / 4.0;
+
+                format!(
+                    r#"<path d="M {} {} C {} {} {} {} {} {}" fill="none" stroke-dasharray="4 3""#,
+                    x1, y1, x1 + bulge, y1, x2 + bulge, y2, x2, y2
+                )
+            } else {
+                let (x1, y1) = position(edge.layer - 1, edge.from);
+
+                format!(r#"<line x1="{}" y1="{}" x2="{}" y2="{}""#, x1, y1, x2, y2)
+            };
+
+            writeln!(
+                svg,
+                r#"{} stroke="{}" stroke-width="{:.2}" stroke-opacity="0.8"><title>{:.3}</title>{}"#,
+                path,
+                color(edge.weight),
+                stroke_width(edge.weight, max),
+                edge.weight,
+                if edge.recurrent { "</path>" } else { "</line>" }
+            ).unwrap();
+        }
+
+        for (layer, &size) in sizes.iter().enumerate() {
+            for neuron in 0..size {
+                let (x, y) = position(layer, neuron);
+
+                let title = match layer {
+                    0 => String::new(),
+                    _ => format!("<title>bias {:.3}</title>", self.layers[layer - 1].biases()[neuron]),
+                };
+
+                writeln!(
+                    svg,
+                    r##"<circle cx="{}" cy="{}" r="{}" fill="#edf2f7" stroke="#2d3748">{}</circle>"##,
+                    x, y, RADIUS, title
+                ).unwrap();
+
+                let label = if layer == 0 {
+                    Some((x - 2.0 * RADIUS, "end", labels.input(neuron)))
+                } else if layer == sizes.len() - 1 {
+                    Some((x + 2.0 * RADIUS, "start", labels.output(neuron)))
+                } else {
+                    None
+                };
+
+                if let Some((x, anchor, text)) = label {
+                    writeln!(
+                        svg,
+                        r#"<text x="{}" y="{}" text-anchor="{}" dominant-baseline="middle">{}</text>"#,
+                        x, y, anchor, escape_xml(&text)
+                    ).unwrap();
+                }
+            }
+        }
+
+        writeln!(svg, "</svg>").unwrap();
+
+        svg
+    }
+
+    /// Number of neurons of each layer, the inputs included.
+    fn layer_sizes(&self) -> impl Iterator<Item = usize> + '_ {
+        std::iter::once(self.input_size())
+            .chain(self.layers.iter().map(|layer| layer.output_size()))
+    }
+
+    fn edges(&self) -> impl Iterator<Item = Edge> + '_ {
+        self.layers.iter().enumerate().flat_map(|(index, layer)| {
+            (0..layer.output_size()).flat_map(move |to| {
+                let inputs = layer.row(to).iter().enumerate().map(move |(from, &weight)| {
+                    Edge { layer: index + 1, from, to, weight, recurrent: false }
+                });
+
+                let recurrent = layer.recurrent_row(to).iter().enumerate().map(move |(from, &weight)| {
+                    Edge { layer: index + 1, from, to, weight, recurrent: true }
+                });
+
+                inputs.chain(recurrent).filter(|edge| edge.weight != 0.0)
+            })
+        })
+    }
+
+    fn max_weight(&self) -> f32 {
+        self.edges().map(|edge| edge.weight.abs()).fold(0.0, f32::max)
+    }
+}
+
+fn color(weight: f32) -> &'static str {
+    if weight >= 0.0 { POSITIVE } else { NEGATIVE }
+}
+
+/// Stroke width, from 0.5 for the weakest weights to 4.0 for the strongest one.
+fn stroke_width(weight: f32, max: f32) -> f32 {
+    0.5 + 3.5 * weight.abs() / max.max(f32::EPSILON)
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
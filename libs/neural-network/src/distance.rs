@@ -0,0 +1,86 @@
+//! Distances between networks, to track the diversity of a population
+//! or to spot near-duplicates among the best networks.
+
+use crate::*;
+
+impl Network {
+    /// Panicking version of `try_weight_distance`.
+    pub fn weight_distance(&self, other: &Self) -> f32 {
+        self.try_weight_distance(other).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Euclidean distance between the weights of two networks of the same topology.
+    pub fn try_weight_distance(&self, other: &Self) -> Result<f32, NetworkError> {
+        let same_topology = self.layers.len() == other.layers.len()
+            && self.layers.iter().zip(&other.layers).all(|(a, b)| {
+                a.input_size() == b.input_size()
+                    && a.output_size() == b.output_size()
+                    && a.kind() == b.kind()
+            });
+
+        if !same_topology {
+            return Err(NetworkError::TopologyMismatch);
+        }
+
+        let sum = self
+            .weights()
+            .into_iter()
+            .zip(other.weights())
+            .map(|(a, b)| (a - b).powi(2))
+            .sum::<f32>();
+
+        Ok(sum.sqrt())
+    }
+
+    /// Panicking version of `try_behavioural_distance`.
+    pub fn behavioural_distance<'a>(
+        &self,
+        other: &Self,
+        probes: impl IntoIterator<Item = &'a [f32]>
+    ) -> f32 {
+        self.try_behavioural_distance(other, probes).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Average Euclidean distance between the outputs of two networks,
+    /// over a sequence of probe inputs. The networks may have different hidden layers.
+    /// Both start from an empty state and go through the probes in order,
+    /// leaving what the original networks remember untouched.
+    pub fn try_behavioural_distance<'a>(
+        &self,
+        other: &Self,
+        probes: impl IntoIterator<Item = &'a [f32]>
+    ) -> Result<f32, NetworkError> {
+        if self.output_size() != other.output_size() {
+            return Err(NetworkError::OutputSizeMismatch {
+                expected: self.output_size(),
+                actual: other.output_size()
+            });
+        }
+
+        let (mut this, mut other) = (self.clone(), other.clone());
+        this.reset_state();
+        other.reset_state();
+
+        let mut sum = 0.0;
+        let mut count = 0;
+
+        for inputs in probes {
+            let outputs = this.try_propagate(inputs.to_vec())?;
+            let other_outputs = other.try_propagate(inputs.to_vec())?;
+
+            sum += outputs
+                .into_iter()
+                .zip(other_outputs)
+                .map(|(a, b)| (a - b).powi(2))
+                .sum::<f32>()
+                .sqrt();
+            count += 1;
+        }
+
+        if count == 0 {
+            return Ok(0.0);
+        }
+
+        Ok(sum / count as f32)
+    }
+}
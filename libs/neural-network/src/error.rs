@@ -0,0 +1,65 @@
+use std::fmt;
+
+/// The reasons why a network can't be built or propagated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NetworkError {
+    /// The topology has less than two layers, or a layer without neurons.
+    EmptyTopology,
+    /// The given weights don't fill every neuron of the topology.
+    TooFewWeights { expected: usize, actual: usize },
+    /// Some weights are left once every neuron of the topology is filled.
+    TooManyWeights { expected: usize, actual: usize },
+    /// The number of inputs doesn't match the size of the input layer.
+    InputSizeMismatch { expected: usize, actual: usize },
+    /// The inputs of a layer don't match the outputs of the previous one.
+    LayerSizeMismatch { layer: usize, expected: usize, actual: usize },
+    /// The initializer has an empty range, or a negative sigma.
+    InvalidInitializer,
+    /// The number of targets doesn't match the size of the output layer.
+    TargetSizeMismatch { expected: usize, actual: usize },
+    /// The number of Hebbian rules doesn't match the number of layers.
+    RuleCountMismatch { expected: usize, actual: usize },
+    /// Two networks that should share the same topology don't.
+    TopologyMismatch,
+    /// Two networks that should have as many outputs don't.
+    OutputSizeMismatch { expected: usize, actual: usize },
+}
+
+impl fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyTopology => {
+                write!(f, "the network needs at least two non-empty layers")
+            }
+            Self::TooFewWeights { expected, actual } => {
+                write!(f, "not enough weights were given: expected {}, got {}", expected, actual)
+            }
+            Self::TooManyWeights { expected, actual } => {
+                write!(f, "too many weights were given: expected {}, got {}", expected, actual)
+            }
+            Self::InputSizeMismatch { expected, actual } => {
+                write!(f, "expected {} inputs, got {}", expected, actual)
+            }
+            Self::LayerSizeMismatch { layer, expected, actual } => {
+                write!(f, "layer {} expects {} inputs, but the previous layer has {} neurons", layer, expected, actual)
+            }
+            Self::InvalidInitializer => {
+                write!(f, "the initializer needs a non-empty range or a non-negative sigma")
+            }
+            Self::TargetSizeMismatch { expected, actual } => {
+                write!(f, "expected {} targets, got {}", expected, actual)
+            }
+            Self::RuleCountMismatch { expected, actual } => {
+                write!(f, "expected {} hebbian rules (one per layer), got {}", expected, actual)
+            }
+            Self::TopologyMismatch => {
+                write!(f, "the networks don't have the same topology")
+            }
+            Self::OutputSizeMismatch { expected, actual } => {
+                write!(f, "expected a network with {} outputs, got {}", expected, actual)
+            }
+        }
+    }
+}
+
+impl std::error::Error for NetworkError {}
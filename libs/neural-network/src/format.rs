@@ -0,0 +1,362 @@
+//! Self-describing serialization of networks, behind the `serde` feature.
+//!
+//! A saved network starts with a header (`format` and `version`) followed by its layers,
+//! so that brains saved with an older layout fail loudly instead of being mis-decoded.
+//! Only `f32` networks can be saved, which is what the simulation uses.
+//! Quantized networks are saved the same way, under a header of their own.
+
+use crate::*;
+use crate::quantize::QuantizedLayer;
+use serde::de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// Name written in the header of every saved network.
+pub const FORMAT_NAME: &str = "lib-neural-network";
+/// Version of the saved layout, bumped whenever it changes.
+/// Version 1 predates recurrent layers: its layers are still read, as dense layers.
+pub const FORMAT_VERSION: u32 = 2;
+
+/// Name written in the header of every saved `QuantizedNetwork`.
+pub const QUANTIZED_FORMAT_NAME: &str = "lib-neural-network-int8";
+/// Version of the saved layout of quantized networks, bumped whenever it changes.
+pub const QUANTIZED_FORMAT_VERSION: u32 = 1;
+
+const FIELDS: &[&str] = &["format", "version", "layers"];
+
+#[derive(Serialize)]
+#[serde(rename = "Network")]
+struct SavedNetwork<'a> {
+    format: &'a str,
+    version: u32,
+    layers: &'a [Layer],
+}
+
+impl Serialize for Network {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SavedNetwork {
+            format: FORMAT_NAME,
+            version: FORMAT_VERSION,
+            layers: &self.layers,
+        }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Network {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_struct("Network", FIELDS, NetworkVisitor)
+    }
+}
+
+/// Checks that a header names the `expected` format, in a version up to `latest`.
+fn check_header<E: de::Error>(expected: &str, latest: u32, format: &str, version: u32) -> Result<(), E> {
+    if format != expected {
+        return Err(E::custom(format!(
+            "expected a `{}` network, found `{}`", expected, format
+        )));
+    }
+
+    if !(1..=latest).contains(&version) {
+        return Err(E::custom(format!(
+            "unsupported network format version {} (expected {})", version, latest
+        )));
+    }
+
+    Ok(())
+}
+
+struct NetworkVisitor;
+
+impl NetworkVisitor {
+    fn check_header<E: de::Error>(format: &str, version: u32) -> Result<(), E> {
+        check_header(FORMAT_NAME, FORMAT_VERSION, format, version)
+    }
+
+    fn build<E: de::Error>(layers: Vec<Layer>) -> Result<Network, E> {
+        Network::try_new(layers).map_err(E::custom)
+    }
+}
+
+impl<'de> Visitor<'de> for NetworkVisitor {
+    type Value = Network;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a `{}` network", FORMAT_NAME)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Network, A::Error> {
+        let format: String = seq.next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let version: u32 = seq.next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+
+        // The header is checked before even trying to read the layers
+        Self::check_header(&format, version)?;
+
+        let layers = seq.next_element_seed(LayersSeed { version })?
+            .ok_or_else(|| de::Error::invalid_length(2, &self))?;
+
+        Self::build(layers)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Network, A::Error> {
+        let mut format: Option<String> = None;
+        let mut version: Option<u32> = None;
+        let mut layers = None;
+
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "format" => format = Some(map.next_value()?),
+                "version" => version = Some(map.next_value()?),
+                "layers" => {
+                    // When the header was written first (as `Serialize` does),
+                    // it is checked before even trying to read the layers
+                    if let (Some(format), Some(version)) = (&format, version) {
+                        Self::check_header(format, version)?;
+                    }
+
+                    // Self-describing formats tell which fields are there,
+                    // so the layers can be read before knowing the version
+                    layers = Some(map.next_value::<Vec<AnyLayerData<f32>>>()?);
+                }
+                _ => return Err(de::Error::unknown_field(&key, FIELDS)),
+            }
+        }
+
+        let format = format.ok_or_else(|| de::Error::missing_field("format"))?;
+        let version = version.ok_or_else(|| de::Error::missing_field("version"))?;
+        Self::check_header(&format, version)?;
+
+        let layers = layers
+            .ok_or_else(|| de::Error::missing_field("layers"))?
+            .into_iter()
+            .map(|data| data.into_layer(version))
+            .collect::<Result<_, _>>()?;
+
+        Self::build(layers)
+    }
+}
+
+/// Reads the layers of a network saved with the given version of the format,
+/// for formats which don't describe their fields.
+struct LayersSeed {
+    version: u32,
+}
+
+impl<'de> DeserializeSeed<'de> for LayersSeed {
+    type Value = Vec<Layer>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Vec<Layer>, D::Error> {
+        if self.version > 1 {
+            return Vec::deserialize(deserializer);
+        }
+
+        Vec::<LayerDataV1<f32>>::deserialize(deserializer)?
+            .into_iter()
+            .map(|data| Layer::try_from(LayerData::from(data)).map_err(de::Error::custom))
+            .collect()
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename = "QuantizedNetwork")]
+struct SavedQuantizedNetwork<'a> {
+    format: &'a str,
+    version: u32,
+    layers: &'a [QuantizedLayer],
+}
+
+impl Serialize for QuantizedNetwork {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SavedQuantizedNetwork {
+            format: QUANTIZED_FORMAT_NAME,
+            version: QUANTIZED_FORMAT_VERSION,
+            layers: self.layers(),
+        }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for QuantizedNetwork {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_struct("QuantizedNetwork", FIELDS, QuantizedNetworkVisitor)
+    }
+}
+
+/// Same as `NetworkVisitor`, with a single version so far.
+struct QuantizedNetworkVisitor;
+
+impl QuantizedNetworkVisitor {
+    fn check_header<E: de::Error>(format: &str, version: u32) -> Result<(), E> {
+        check_header(QUANTIZED_FORMAT_NAME, QUANTIZED_FORMAT_VERSION, format, version)
+    }
+
+    fn build<E: de::Error>(layers: Vec<QuantizedLayer>) -> Result<QuantizedNetwork, E> {
+        QuantizedNetwork::try_new(layers).map_err(E::custom)
+    }
+}
+
+impl<'de> Visitor<'de> for QuantizedNetworkVisitor {
+    type Value = QuantizedNetwork;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a `{}` network", QUANTIZED_FORMAT_NAME)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<QuantizedNetwork, A::Error> {
+        let format: String = seq.next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let version: u32 = seq.next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+
+        Self::check_header(&format, version)?;
+
+        let layers = seq.next_element()?
+            .ok_or_else(|| de::Error::invalid_length(2, &self))?;
+
+        Self::build(layers)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<QuantizedNetwork, A::Error> {
+        let mut format: Option<String> = None;
+        let mut version: Option<u32> = None;
+        let mut layers = None;
+
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "format" => format = Some(map.next_value()?),
+                "version" => version = Some(map.next_value()?),
+                "layers" => {
+                    if let (Some(format), Some(version)) = (&format, version) {
+                        Self::check_header(format, version)?;
+                    }
+
+                    layers = Some(map.next_value()?);
+                }
+                _ => return Err(de::Error::unknown_field(&key, FIELDS)),
+            }
+        }
+
+        let format = format.ok_or_else(|| de::Error::missing_field("format"))?;
+        let version = version.ok_or_else(|| de::Error::missing_field("version"))?;
+        Self::check_header(&format, version)?;
+
+        Self::build(layers.ok_or_else(|| de::Error::missing_field("layers"))?)
+    }
+}
+
+/// The raw fields of a layer, checked before turning them into a `Layer`.
+#[derive(Deserialize)]
+pub(crate) struct LayerData<S> {
+    input_size: usize,
+    weights: Vec<S>,
+    biases: Vec<S>,
+    activation: Activation,
+    kind: LayerKind,
+    recurrent_weights: Vec<S>,
+}
+
+/// The raw fields of a layer saved with version 1, when every layer was dense.
+#[derive(Deserialize)]
+struct LayerDataV1<S> {
+    input_size: usize,
+    weights: Vec<S>,
+    biases: Vec<S>,
+    activation: Activation,
+}
+
+/// The raw fields of a layer of any version, for self-describing formats.
+#[derive(Deserialize)]
+struct AnyLayerData<S> {
+    input_size: usize,
+    weights: Vec<S>,
+    biases: Vec<S>,
+    activation: Activation,
+    kind: Option<LayerKind>,
+    recurrent_weights: Option<Vec<S>>,
+}
+
+impl AnyLayerData<f32> {
+    /// Checks that the layer has the fields of `version`, then turns it into a `Layer`.
+    fn into_layer<E: de::Error>(self, version: u32) -> Result<Layer, E> {
+        let data = if version == 1 {
+            if self.kind.is_some() || self.recurrent_weights.is_some() {
+                return Err(E::custom("layers of version 1 have no kind nor recurrent weights"));
+            }
+
+            LayerData::from(LayerDataV1 {
+                input_size: self.input_size,
+                weights: self.weights,
+                biases: self.biases,
+                activation: self.activation,
+            })
+        } else {
+            LayerData {
+                input_size: self.input_size,
+                weights: self.weights,
+                biases: self.biases,
+                activation: self.activation,
+                kind: self.kind.ok_or_else(|| E::missing_field("kind"))?,
+                recurrent_weights: self
+                    .recurrent_weights
+                    .ok_or_else(|| E::missing_field("recurrent_weights"))?,
+            }
+        };
+
+        Layer::try_from(data).map_err(E::custom)
+    }
+}
+
+impl<S> From<LayerDataV1<S>> for LayerData<S> {
+    fn from(data: LayerDataV1<S>) -> Self {
+        Self {
+            input_size: data.input_size,
+            weights: data.weights,
+            biases: data.biases,
+            activation: data.activation,
+            kind: LayerKind::Dense,
+            recurrent_weights: Vec::new(),
+        }
+    }
+}
+
+impl<S: Scalar> TryFrom<LayerData<S>> for Layer<S> {
+    type Error = String;
+
+    fn try_from(data: LayerData<S>) -> Result<Self, Self::Error> {
+        if data.input_size == 0 || data.biases.is_empty() {
+            return Err(NetworkError::EmptyTopology.to_string());
+        }
+
+        let expected = data.input_size * data.biases.len();
+
+        if data.weights.len() != expected {
+            return Err(format!(
+                "a layer of {} neurons with {} inputs needs {} weights, got {}",
+                data.biases.len(), data.input_size, expected, data.weights.len()
+            ));
+        }
+
+        let recurrent_size = match data.kind {
+            LayerKind::Dense => 0,
+            LayerKind::Recurrent => data.biases.len(),
+        };
+        let expected = recurrent_size * data.biases.len();
+
+        if data.recurrent_weights.len() != expected {
+            return Err(format!(
+                "a {:?} layer of {} neurons needs {} recurrent weights, got {}",
+                data.kind, data.biases.len(), expected, data.recurrent_weights.len()
+            ));
+        }
+
+        Ok(Self {
+            input_size: data.input_size,
+            weights: data.weights,
+            biases: data.biases,
+            activation: data.activation,
+            kind: data.kind,
+            recurrent_weights: data.recurrent_weights,
+            state: vec![S::ZERO; recurrent_size],
+        })
+    }
+}
@@ -0,0 +1,115 @@
+//! Hebbian plasticity, so that networks keep learning while they are being used.
+//!
+//! After each propagation, every connection of a layer moves according to the ABCD rule
+//! of that layer, given the value it carried (`pre`) and the output of its neuron (`post`):
+//! `Δw = learning_rate * (a * pre * post + b * pre + c * post + d)`.
+//! Biases are left untouched.
+
+use crate::*;
+
+/// Weights are kept within `±WEIGHT_LIMIT`, so that plasticity can't make them run away.
+pub const WEIGHT_LIMIT: f32 = 4.0;
+
+/// The coefficients of the ABCD rule of one layer.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HebbianRule {
+    pub learning_rate: f32,
+    /// Weight of the correlation between `pre` and `post`
+    pub a: f32,
+    /// Weight of `pre` alone
+    pub b: f32,
+    /// Weight of `post` alone
+    pub c: f32,
+    /// Constant drift
+    pub d: f32,
+}
+
+impl HebbianRule {
+    /// Number of coefficients of a rule, i.e. of genes in a chromosome.
+    pub const GENES: usize = 5;
+
+    /// Draws a rule with a small learning rate, and coefficients in `-1.0..=1.0`.
+    pub fn random(rng: &mut dyn RngCore) -> Self {
+        Self {
+            learning_rate: rng.gen_range(0.0..=0.1),
+            a: rng.gen_range(-1.0..=1.0),
+            b: rng.gen_range(-1.0..=1.0),
+            c: rng.gen_range(-1.0..=1.0),
+            d: rng.gen_range(-1.0..=1.0),
+        }
+    }
+
+    /// Returns the coefficients for exportation, learning rate first.
+    pub fn weights(&self) -> [f32; Self::GENES] {
+        [self.learning_rate, self.a, self.b, self.c, self.d]
+    }
+
+    /// Initializes a rule from coefficients given in the order of `weights()`.
+    pub fn from_weights(weights: &mut dyn Iterator<Item = f32>) -> Self {
+        let mut next = || weights.next().expect("Not enough weights were given");
+
+        Self { learning_rate: next(), a: next(), b: next(), c: next(), d: next() }
+    }
+
+    /// Change of a weight, given the value it carried and the output of its neuron.
+    pub fn delta(&self, pre: f32, post: f32) -> f32 {
+        self.learning_rate * (self.a * pre * post + self.b * pre + self.c * post + self.d)
+    }
+}
+
+impl Network {
+    /// Panicking version of `try_propagate_plastic`.
+    pub fn propagate_plastic(&mut self, inputs: &[f32], rules: &[HebbianRule]) -> Vec<f32> {
+        self.try_propagate_plastic(inputs, rules).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as `try_propagate`, but then updates the weights of each layer
+    /// according to its rule in `rules`, which holds one rule per layer.
+    pub fn try_propagate_plastic(
+        &mut self,
+        inputs: &[f32],
+        rules: &[HebbianRule]
+    ) -> Result<Vec<f32>, NetworkError> {
+        if rules.len() != self.layers.len() {
+            return Err(NetworkError::RuleCountMismatch {
+                expected: self.layers.len(),
+                actual: rules.len()
+            });
+        }
+
+        // What the recurrent layers fed back into this propagation
+        let states: Vec<_> = self.layers.iter().map(|layer| layer.state().to_vec()).collect();
+        let trace = self.try_propagate_traced(inputs)?;
+
+        for (index, (layer, rule)) in self.layers.iter_mut().zip(rules).enumerate() {
+            let inputs = match index {
+                0 => inputs,
+                _ => &trace.layers[index - 1].activations,
+            };
+
+            layer.learn(rule, inputs, &states[index], &trace.layers[index].activations);
+        }
+
+        Ok(trace.outputs().to_vec())
+    }
+}
+
+impl Layer {
+    fn learn(&mut self, rule: &HebbianRule, inputs: &[f32], state: &[f32], outputs: &[f32]) {
+        let input_size = self.input_size;
+        let recurrent_size = state.len();
+
+        for (neuron, &post) in outputs.iter().enumerate() {
+            let row = &mut self.weights[neuron * input_size..(neuron + 1) * input_size];
+            let recurrent_row =
+                &mut self.recurrent_weights[neuron * recurrent_size..(neuron + 1) * recurrent_size];
+
+            let weights = row.iter_mut().zip(inputs).chain(recurrent_row.iter_mut().zip(state));
+
+            for (weight, &pre) in weights {
+                *weight = (*weight + rule.delta(pre, post)).clamp(-WEIGHT_LIMIT, WEIGHT_LIMIT);
+            }
+        }
+    }
+}
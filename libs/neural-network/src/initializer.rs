@@ -0,0 +1,73 @@
+use crate::*;
+use rand_distr::{Distribution, Normal};
+
+/// How the weights of a random layer are drawn.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Initializer {
+    /// Weights and biases drawn uniformly from `low..=high`
+    Uniform { low: f32, high: f32 },
+    /// Xavier/Glorot: weights drawn uniformly from `±sqrt(6 / (fan_in + fan_out))`,
+    /// which suits `Tanh` and `Sigmoid`. Biases start at zero.
+    Xavier,
+    /// He: weights drawn from a normal distribution of sigma `sqrt(2 / fan_in)`,
+    /// which suits `ReLU`. Biases start at zero.
+    He,
+    /// Weights and biases drawn from a normal distribution of given sigma
+    Normal(f32),
+    /// Every weight and bias set to zero
+    Zeros,
+}
+
+impl Default for Initializer {
+    /// Uniform in `-1.0..=1.0`, regardless of the size of the layer.
+    fn default() -> Self {
+        Self::Uniform { low: -1.0, high: 1.0 }
+    }
+}
+
+impl Initializer {
+    /// Makes sure that the weights can be drawn.
+    pub(crate) fn check(&self) -> Result<(), NetworkError> {
+        let valid = match *self {
+            Self::Uniform { low, high } => low <= high,
+            Self::Normal(sigma) => sigma >= 0.0,
+            Self::Xavier | Self::He | Self::Zeros => true,
+        };
+
+        if !valid {
+            return Err(NetworkError::InvalidInitializer);
+        }
+
+        Ok(())
+    }
+
+    /// Draws a weight of a neuron with `fan_in` inputs, in a layer of `fan_out` neurons.
+    pub fn weight(&self, rng: &mut dyn RngCore, fan_in: usize, fan_out: usize) -> f32 {
+        match *self {
+            Self::Uniform { low, high } => rng.gen_range(low..=high),
+            Self::Xavier => {
+                let limit = (6.0 / (fan_in + fan_out) as f32).sqrt();
+
+                rng.gen_range(-limit..=limit)
+            }
+            Self::He => normal(rng, (2.0 / fan_in as f32).sqrt()),
+            Self::Normal(sigma) => normal(rng, sigma),
+            Self::Zeros => 0.0,
+        }
+    }
+
+    /// Draws the bias of a neuron with `fan_in` inputs, in a layer of `fan_out` neurons.
+    pub fn bias(&self, rng: &mut dyn RngCore, fan_in: usize, fan_out: usize) -> f32 {
+        match self {
+            Self::Xavier | Self::He => 0.0,
+            _ => self.weight(rng, fan_in, fan_out),
+        }
+    }
+}
+
+fn normal(rng: &mut dyn RngCore, sigma: f32) -> f32 {
+    Normal::new(0.0, sigma)
+        .expect("sigma should have been checked")
+        .sample(rng)
+}
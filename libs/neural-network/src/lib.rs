@@ -1,64 +1,201 @@
-use rand::Rng;
-
+use rand::{Rng, RngCore};
+pub use crate::{
+    activation::*, backprop::*, batch::*, diagram::*, error::*, hebbian::*, initializer::*,
+    neat::*, quantize::*, scalar::*, sparse::*, trace::*, workspace::*,
+};
+
+pub mod activation;
+pub mod backprop;
+pub mod batch;
+mod converted;
+pub mod diagram;
+pub mod distance;
+pub mod error;
+#[cfg(feature = "serde")]
+pub mod format;
+pub mod hebbian;
+pub mod initializer;
+pub mod neat;
+pub mod quantize;
+pub mod scalar;
+pub mod sparse;
 pub mod test;
+pub mod trace;
+pub mod workspace;
 
 /// The structure of a neural network, capable of propagating an input through layers.
-#[derive(Debug)]
-pub struct Network {
-    layers: Vec<Layer>,
+/// Its weights are `f32` by default, but can be any `Scalar`.
+/// Two networks are equal when their layers are, whatever their recurrent layers remember.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Network<S: Scalar = f32> {
+    layers: Vec<Layer<S>>,
 }
 
-impl Network {
-    pub fn new(layers: Vec<Layer>) -> Self {
-        assert!(layers.len() > 1);
+impl<S: Scalar> Network<S> {
+    /// Panicking version of `try_new`.
+    pub fn new(layers: Vec<Layer<S>>) -> Self {
+        Self::try_new(layers).unwrap_or_else(|err| panic!("{}", err))
+    }
 
-        Self { layers }
+    /// Initializes a network from given layers,
+    /// making sure that each layer takes as many inputs as the previous one has outputs.
+    pub fn try_new(layers: Vec<Layer<S>>) -> Result<Self, NetworkError> {
+        // A single layer maps the inputs straight to the outputs
+        if layers.is_empty() {
+            return Err(NetworkError::EmptyTopology);
+        }
+
+        // Layers built by hand with `Layer::new` may not fit each other
+        for (index, pair) in layers.windows(2).enumerate() {
+            if pair[1].input_size() != pair[0].output_size() {
+                return Err(NetworkError::LayerSizeMismatch {
+                    layer: index + 1,
+                    expected: pair[1].input_size(),
+                    actual: pair[0].output_size()
+                });
+            }
+        }
+
+        Ok(Self { layers })
+    }
+
+    /// Panicking version of `try_propagate`.
+    pub fn propagate(&mut self, inputs: Vec<S>) -> Vec<S> {
+        self.try_propagate(inputs).unwrap_or_else(|err| panic!("{}", err))
     }
 
     /// Given `inputs`, computes the output of the neural network.
-    pub fn propagate(&self, inputs: Vec<f32>) -> Vec<f32> {
-        self.layers
-            .iter()
-            .fold(inputs, |inputs, layer| layer.propagate(inputs))
+    pub fn try_propagate(&mut self, inputs: Vec<S>) -> Result<Vec<S>, NetworkError> {
+        let mut workspace = Workspace::new();
+        self.try_propagate_into(&inputs, &mut workspace)?;
+
+        Ok(workspace.front)
     }
 
-    /// Initializes a new neural network with random layers.
-    pub fn random(layers: &[LayerTopology]) -> Self {
-        assert!(layers.len() > 1);
+    /// Panicking version of `try_propagate_into`.
+    pub fn propagate_into<'a>(
+        &mut self,
+        inputs: &[S],
+        workspace: &'a mut Workspace<S>
+    ) -> &'a [S] {
+        self.try_propagate_into(inputs, workspace)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
 
-        let layers = layers
-            .windows(2)
-            .map(|layers| {
-                Layer::random(layers[0].neurons, layers[1].neurons)
-            })
-            .collect();
+    /// Given `inputs`, computes the output of the neural network
+    /// using the buffers of `workspace`, without allocating once they are warm.
+    pub fn try_propagate_into<'a>(
+        &mut self,
+        inputs: &[S],
+        workspace: &'a mut Workspace<S>
+    ) -> Result<&'a [S], NetworkError> {
+        self.check_inputs(inputs)?;
+
+        let Workspace { front, back } = workspace;
+
+        // The output of each layer always ends up in `front`
+        self.layers[0].propagate_into(inputs, front);
+
+        for layer in &mut self.layers[1..] {
+            layer.propagate_into(front, back);
+            std::mem::swap(front, back);
+        }
 
-        Self { layers }
+        Ok(front)
+    }
+
+    /// Checks that `inputs` can be propagated through the network.
+    pub(crate) fn check_inputs(&self, inputs: &[S]) -> Result<(), NetworkError> {
+        if inputs.len() != self.input_size() {
+            return Err(NetworkError::InputSizeMismatch {
+                expected: self.input_size(),
+                actual: inputs.len()
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Computes the outputs of the neural network for a whole batch of inputs.
+    /// `inputs` is a row-major matrix with one row of inputs per sample,
+    /// and so is the returned matrix of outputs.
+    /// Samples are independent: recurrent layers start each of them from their current state,
+    /// which they keep. To go through a sequence of inputs, call `propagate` for each of them.
+    pub fn propagate_batch(&self, inputs: &[S]) -> Vec<S> {
+        let mut workspace = Workspace::new();
+        self.propagate_batch_into(inputs, &mut workspace);
+
+        workspace.front
+    }
+
+    /// Same as `propagate_batch`, but using the buffers of `workspace`.
+    pub fn propagate_batch_into<'a>(
+        &self,
+        inputs: &[S],
+        workspace: &'a mut Workspace<S>
+    ) -> &'a [S] {
+        let Workspace { front, back } = workspace;
+
+        self.layers[0].propagate_batch_into(inputs, front);
+
+        for layer in &self.layers[1..] {
+            layer.propagate_batch_into(front, back);
+            std::mem::swap(front, back);
+        }
+
+        front
     }
 
     /// Returns a Vector containing the weights of the network for exportation.
-    pub fn weights(&self) -> Vec<f32> {
+    /// For each neuron, its bias is followed by the weights of its inputs,
+    /// and then by its recurrent weights if the layer is recurrent.
+    pub fn weights(&self) -> Vec<S> {
         let mut weights = Vec::new();
 
         for layer in &self.layers {
-            for neuron in &layer.neurons {
-                weights.push(neuron.bias);
-
-                for weight in &neuron.weights {
-                    weights.push(*weight);
-                }
+            for neuron in 0..layer.output_size() {
+                weights.push(layer.biases[neuron]);
+                weights.extend_from_slice(layer.row(neuron));
+                weights.extend_from_slice(layer.recurrent_row(neuron));
             }
         }
 
         weights
     }
 
-    /// Initializes a network from given weights
+    /// Forgets what the recurrent layers remember from the previous propagations.
+    pub fn reset_state(&mut self) {
+        for layer in &mut self.layers {
+            layer.reset_state();
+        }
+    }
+
+    /// Panicking version of `try_from_weights`.
     pub fn from_weights(
         layers: &[LayerTopology],
-        weights: impl IntoIterator<Item = f32>
+        weights: impl IntoIterator<Item = S>
     ) -> Self {
-        assert!(layers.len() > 1);
+        Self::try_from_weights(layers, weights).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Initializes a network from given weights,
+    /// making sure that there are exactly as many weights as the topology needs.
+    pub fn try_from_weights(
+        layers: &[LayerTopology],
+        weights: impl IntoIterator<Item = S>
+    ) -> Result<Self, NetworkError> {
+        LayerTopology::check(layers)?;
+
+        let weights: Vec<_> = weights.into_iter().collect();
+        let expected = LayerTopology::weights_count(layers);
+
+        if weights.len() < expected {
+            return Err(NetworkError::TooFewWeights { expected, actual: weights.len() });
+        }
+
+        if weights.len() > expected {
+            return Err(NetworkError::TooManyWeights { expected, actual: weights.len() });
+        }
 
         let mut weights = weights.into_iter();
 
@@ -68,100 +205,311 @@ impl Network {
                 Layer::from_weights(
                     layers[0].neurons,
                     layers[1].neurons,
+                    layers[1].activation,
+                    layers[1].kind,
                     &mut weights
                 )
             })
             .collect();
 
-        if weights.next().is_some() {
-            panic!("Too many weights were given.")
-        }
+        Ok(Self { layers })
+    }
 
-        Self { layers} 
+    /// Getter for the layers of the network
+    pub fn layers(&self) -> &[Layer<S>] {
+        &self.layers
     }
-} 
 
-#[derive(Debug)]
-pub struct Layer {
-    neurons: Vec<Neuron>,
-}
+    /// Number of inputs expected by the network
+    pub fn input_size(&self) -> usize {
+        self.layers[0].input_size()
+    }
 
-impl Layer {
-    /// Initializes a layer of deterministic neurons.
-    pub fn new(neurons: Vec<Neuron>) -> Self {
-        Self { neurons }
+    /// Number of outputs of the network
+    pub fn output_size(&self) -> usize {
+        self.layers[self.layers.len() - 1].output_size()
     }
+}
 
-    /// Propagates the output of the previous layer through the layer.
-    fn propagate(&self, inputs: Vec<f32>) -> Vec<f32> {
-        self.neurons
-            .iter()
-            .map(|neuron| neuron.propagate(&inputs))
-            .collect()
+impl Network {
+    /// Panicking version of `try_random`.
+    pub fn random(
+        rng: &mut dyn RngCore,
+        layers: &[LayerTopology],
+        initializer: Initializer
+    ) -> Self {
+        Self::try_random(rng, layers, initializer).unwrap_or_else(|err| panic!("{}", err))
     }
 
-    /// Initializes layer of random neurons.
-    pub fn random(input_neurons: usize, output_neurons: usize) -> Self {
-        let neurons = (0..output_neurons)
-            .map(|_| Neuron::random(&mut rand::thread_rng(), input_neurons))
+    /// Initializes a new neural network with random layers,
+    /// whose weights are drawn by `initializer`.
+    pub fn try_random(
+        rng: &mut dyn RngCore,
+        layers: &[LayerTopology],
+        initializer: Initializer
+    ) -> Result<Self, NetworkError> {
+        LayerTopology::check(layers)?;
+        initializer.check()?;
+
+        let layers = layers
+            .windows(2)
+            .map(|layers| {
+                Layer::random(
+                    rng,
+                    layers[0].neurons,
+                    layers[1].neurons,
+                    layers[1].activation,
+                    layers[1].kind,
+                    initializer
+                )
+            })
             .collect();
 
-        Self { neurons }
+        Ok(Self { layers })
+    }
+}
+
+/// A fully-connected layer, storing the weights of its neurons
+/// as a contiguous row-major matrix (one row per neuron).
+/// Recurrent layers also store the weights applied to their previous outputs,
+/// and the previous outputs themselves.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "format::LayerData<S>"))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "S: Scalar + serde::Deserialize<'de>")))]
+pub struct Layer<S: Scalar = f32> {
+    input_size: usize,
+    weights: Vec<S>,
+    biases: Vec<S>,
+    activation: Activation,
+    kind: LayerKind,
+    /// Row-major matrix of `output_size` rows and columns, empty if the layer is dense
+    recurrent_weights: Vec<S>,
+    /// Outputs of the previous propagation, empty if the layer is dense
+    #[cfg_attr(feature = "serde", serde(skip))]
+    state: Vec<S>,
+}
+
+impl<S: Scalar> Layer<S> {
+    /// Initializes a dense layer of deterministic neurons.
+    pub fn new(neurons: Vec<Neuron<S>>, activation: Activation) -> Self {
+        Self::with_kind(neurons, activation, LayerKind::Dense)
+    }
+
+    /// Initializes a recurrent layer of deterministic neurons.
+    /// The weights of each neuron are the weights of its inputs,
+    /// followed by the weights of the previous outputs of the layer.
+    pub fn recurrent(neurons: Vec<Neuron<S>>, activation: Activation) -> Self {
+        Self::with_kind(neurons, activation, LayerKind::Recurrent)
+    }
+
+    /// Initializes a layer of deterministic neurons of the given kind.
+    pub fn with_kind(neurons: Vec<Neuron<S>>, activation: Activation, kind: LayerKind) -> Self {
+        assert!(!neurons.is_empty());
+
+        let recurrent_size = match kind {
+            LayerKind::Dense => 0,
+            LayerKind::Recurrent => neurons.len(),
+        };
+        assert!(neurons[0].weights.len() > recurrent_size);
+
+        let input_size = neurons[0].weights.len() - recurrent_size;
+        let mut weights = Vec::with_capacity(input_size * neurons.len());
+        let mut recurrent_weights = Vec::with_capacity(recurrent_size * neurons.len());
+        let mut biases = Vec::with_capacity(neurons.len());
+
+        for neuron in neurons {
+            assert_eq!(neuron.weights.len(), input_size + recurrent_size);
+
+            biases.push(neuron.bias);
+            weights.extend_from_slice(&neuron.weights[..input_size]);
+            recurrent_weights.extend_from_slice(&neuron.weights[input_size..]);
+        }
+
+        let state = vec![S::ZERO; recurrent_size];
+
+        Self { input_size, weights, biases, activation, kind, recurrent_weights, state }
+    }
+
+    /// Propagates the output of the previous layer through the layer,
+    /// writing the activations of the neurons into `outputs`.
+    fn propagate_into(&mut self, inputs: &[S], outputs: &mut Vec<S>) {
+        assert_eq!(inputs.len(), self.input_size);
+
+        outputs.clear();
+        self.extend_outputs(inputs, outputs);
+        self.remember(outputs);
+    }
+
+    /// Propagates a row-major matrix of inputs (one row per sample) through the layer,
+    /// each sample starting from the current state.
+    fn propagate_batch_into(&self, inputs: &[S], outputs: &mut Vec<S>) {
+        assert_eq!(inputs.len() % self.input_size, 0);
+
+        outputs.clear();
+
+        for inputs in inputs.chunks_exact(self.input_size) {
+            self.extend_outputs(inputs, outputs);
+        }
+    }
+
+    /// Pushes the activation of each neuron for one sample to `outputs`.
+    fn extend_outputs(&self, inputs: &[S], outputs: &mut Vec<S>) {
+        outputs.extend(
+            (0..self.output_size()).map(|neuron| {
+                self.activation.apply(self.pre_activation(inputs, neuron))
+            })
+        );
+    }
+
+    /// Computes the weighted sum of the inputs of one neuron, bias included.
+    pub(crate) fn pre_activation(&self, inputs: &[S], neuron: usize) -> S {
+        let mut output = inputs
+            .iter()
+            .zip(self.row(neuron))
+            .map(|(&input, &weight)| input * weight)
+            .sum::<S>();
+
+        for (&state, &weight) in self.state.iter().zip(self.recurrent_row(neuron)) {
+            output += state * weight;
+        }
+
+        output + self.biases[neuron]
+    }
+
+    /// Stores the outputs of a propagation, if the layer is recurrent.
+    pub(crate) fn remember(&mut self, outputs: &[S]) {
+        let size = self.state.len();
+        self.state.copy_from_slice(&outputs[..size]);
     }
 
     /// Initializes a layer from given weights.
     pub fn from_weights(
         input_size: usize,
         output_size: usize,
-        weigths: &mut dyn Iterator<Item = f32>
+        activation: Activation,
+        kind: LayerKind,
+        weigths: &mut dyn Iterator<Item = S>
     ) -> Self {
         let neurons = (0..output_size)
-            .map(|_| Neuron::from_weights(input_size, weigths))
+            .map(|_| {
+                Neuron::from_weights(kind.weights_per_neuron(input_size, output_size), weigths)
+            })
             .collect();
 
-        Self { neurons }
+        Self::with_kind(neurons, activation, kind)
     }
-}
 
-#[derive(Debug)]
-pub struct Neuron {
-    bias: f32,
-    weights: Vec<f32>,
-}
+    /// Forgets the outputs of the previous propagation.
+    pub fn reset_state(&mut self) {
+        self.state.fill(S::ZERO);
+    }
 
-impl Neuron {
-    /// Initializes a deterministic neuron from given bias and weights.
-    pub fn new(bias: f32, weights: Vec<f32>) -> Self {
-        Self { bias, weights }
+    /// Number of inputs of each neuron
+    pub fn input_size(&self) -> usize {
+        self.input_size
     }
 
-    /// Computes the activation of the neuron, given the inputs.
-    fn propagate(&self, inputs: &[f32]) -> f32 {
-        assert_eq!(inputs.len(), self.weights.len());
+    /// Number of neurons of the layer
+    pub fn output_size(&self) -> usize {
+        self.biases.len()
+    }
 
-        let output = inputs
-            .iter()
-            .zip(&self.weights)
-            .map(|(input, weight)| input * weight)
-            .sum::<f32>();
+    /// Getter for the row-major weight matrix of the layer
+    pub fn weights(&self) -> &[S] {
+        &self.weights
+    }
 
-        (output + self.bias).max(0.0)
+    /// Getter for the biases of the neurons
+    pub fn biases(&self) -> &[S] {
+        &self.biases
     }
 
-    /// Initializes neuron with randoms weights and a random bias.
-    pub fn random(rng: &mut dyn rand::RngCore, output_size: usize) -> Self {
-        let bias = rng.gen_range(-1.0..=1.0);
+    /// Getter for the activation function of the layer
+    pub fn activation(&self) -> Activation {
+        self.activation
+    }
 
-        let weights = (0..output_size)
-            .map(|_| rng.gen_range(-1.0..=1.0))
+    /// Getter for the kind of the layer
+    pub fn kind(&self) -> LayerKind {
+        self.kind
+    }
+
+    /// Getter for the row-major matrix of the weights applied to the previous outputs,
+    /// empty if the layer is dense
+    pub fn recurrent_weights(&self) -> &[S] {
+        &self.recurrent_weights
+    }
+
+    /// Getter for the outputs of the previous propagation, empty if the layer is dense
+    pub fn state(&self) -> &[S] {
+        &self.state
+    }
+
+    /// Weights of the inputs of one neuron
+    pub(crate) fn row(&self, neuron: usize) -> &[S] {
+        &self.weights[neuron * self.input_size..(neuron + 1) * self.input_size]
+    }
+
+    /// Weights of the previous outputs for one neuron, empty if the layer is dense
+    pub(crate) fn recurrent_row(&self, neuron: usize) -> &[S] {
+        let size = self.state.len();
+
+        &self.recurrent_weights[neuron * size..(neuron + 1) * size]
+    }
+}
+
+impl Layer {
+    /// Initializes layer of random neurons, whose weights are drawn by `initializer`.
+    pub fn random(
+        rng: &mut dyn RngCore,
+        input_neurons: usize,
+        output_neurons: usize,
+        activation: Activation,
+        kind: LayerKind,
+        initializer: Initializer
+    ) -> Self {
+        // The recurrent weights of a neuron are inputs as well
+        let fan_in = kind.weights_per_neuron(input_neurons, output_neurons);
+
+        let neurons = (0..output_neurons)
+            .map(|_| Neuron::with_initializer(rng, fan_in, initializer, output_neurons))
             .collect();
 
+        Self::with_kind(neurons, activation, kind)
+    }
+}
+
+/// Layers are equal when they share the same weights, biases, activation and kind,
+/// even if they remember different previous outputs.
+impl<S: Scalar> PartialEq for Layer<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.input_size == other.input_size
+            && self.weights == other.weights
+            && self.biases == other.biases
+            && self.activation == other.activation
+            && self.kind == other.kind
+            && self.recurrent_weights == other.recurrent_weights
+    }
+}
+
+/// A single neuron, used to build or inspect a `Layer`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Neuron<S = f32> {
+    bias: S,
+    weights: Vec<S>,
+}
+
+impl<S: Scalar> Neuron<S> {
+    /// Initializes a deterministic neuron from given bias and weights.
+    pub fn new(bias: S, weights: Vec<S>) -> Self {
         Self { bias, weights }
     }
 
     pub fn from_weights(
         output_neurons: usize,
-        weights: &mut dyn Iterator<Item = f32>
+        weights: &mut dyn Iterator<Item = S>
     ) -> Self {
         let bias = weights.next().expect("Not enough weights were given");
 
@@ -171,9 +519,84 @@ impl Neuron {
 
         Self { bias, weights }
     }
-} 
+}
+
+impl Neuron {
+    /// Initializes neuron with randoms weights and a random bias.
+    pub fn random(rng: &mut dyn RngCore, output_size: usize) -> Self {
+        Self::with_initializer(rng, output_size, Initializer::default(), 1)
+    }
 
-/// A structure containing the number of neurons of a layer.
+    /// Initializes neuron with weights and a bias drawn by `initializer`,
+    /// `fan_out` being the number of neurons of its layer.
+    pub fn with_initializer(
+        rng: &mut dyn RngCore,
+        output_size: usize,
+        initializer: Initializer,
+        fan_out: usize
+    ) -> Self {
+        let bias = initializer.bias(rng, output_size, fan_out);
+
+        let weights = (0..output_size)
+            .map(|_| initializer.weight(rng, output_size, fan_out))
+            .collect();
+
+        Self { bias, weights }
+    }
+}
+
+/// Whether a layer only sees its inputs, or also its own previous outputs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LayerKind {
+    /// Fully-connected feed-forward layer
+    #[default]
+    Dense,
+    /// Elman layer: each neuron also receives the outputs of the layer
+    /// at the previous propagation, which gives the network a memory.
+    Recurrent,
+}
+
+impl LayerKind {
+    /// Number of weights of each neuron (bias excluded).
+    fn weights_per_neuron(&self, input_size: usize, output_size: usize) -> usize {
+        match self {
+            Self::Dense => input_size,
+            Self::Recurrent => input_size + output_size,
+        }
+    }
+}
+
+/// A structure containing the number of neurons of a layer,
+/// the activation function applied to them, and the kind of the layer.
+/// The activation and kind of the input layer are ignored.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LayerTopology {
-    pub neurons: usize
-}
\ No newline at end of file
+    pub neurons: usize,
+    pub activation: Activation,
+    pub kind: LayerKind
+}
+
+impl LayerTopology {
+    /// Makes sure that `layers` describe a network with at least two non-empty layers.
+    fn check(layers: &[LayerTopology]) -> Result<(), NetworkError> {
+        if layers.len() < 2 || layers.iter().any(|layer| layer.neurons == 0) {
+            return Err(NetworkError::EmptyTopology);
+        }
+
+        Ok(())
+    }
+
+    /// Number of weights (biases included) of a network with the given layers.
+    pub fn weights_count(layers: &[LayerTopology]) -> usize {
+        layers
+            .windows(2)
+            .map(|layers| {
+                let weights = layers[1].kind.weights_per_neuron(layers[0].neurons, layers[1].neurons);
+
+                (weights + 1) * layers[1].neurons
+            })
+            .sum()
+    }
+}
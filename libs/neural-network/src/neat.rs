@@ -0,0 +1,492 @@
+//! NEAT-style genomes, whose hidden structure evolves along with their weights.
+//!
+//! A genome is a graph of node genes linked by connection genes.
+//! Each structural novelty gets an innovation number from an `Innovations` tracker
+//! shared by the whole population, so that crossover can line up the genes
+//! that two genomes inherited from the same mutation.
+
+use crate::*;
+use std::collections::HashMap;
+
+/// Hands out node ids and innovation numbers, and remembers which ones were given
+/// so that the same mutation happening twice in a generation yields the same genes.
+#[derive(Clone, Debug, Default)]
+pub struct Innovations {
+    next_node: usize,
+    next_innovation: usize,
+    /// Innovation number of the connection between two nodes
+    connections: HashMap<(usize, usize), usize>,
+    /// Node created by splitting the connection of given innovation number
+    splits: HashMap<usize, usize>,
+}
+
+impl Innovations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Innovation number of the connection going from node `from` to node `to`.
+    fn connection(&mut self, from: usize, to: usize) -> usize {
+        let next_innovation = &mut self.next_innovation;
+
+        *self.connections.entry((from, to)).or_insert_with(|| {
+            *next_innovation += 1;
+            *next_innovation - 1
+        })
+    }
+
+    /// Id of the node inserted in the middle of the connection of given innovation number.
+    fn split(&mut self, innovation: usize) -> usize {
+        let next_node = &mut self.next_node;
+
+        *self.splits.entry(innovation).or_insert_with(|| {
+            *next_node += 1;
+            *next_node - 1
+        })
+    }
+
+    /// Makes sure that new nodes don't reuse the ids of the first `count` ones.
+    fn reserve_nodes(&mut self, count: usize) {
+        self.next_node = self.next_node.max(count);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeKind {
+    Input,
+    Hidden,
+    Output,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NodeGene {
+    pub id: usize,
+    pub kind: NodeKind,
+    /// Ignored for input nodes
+    pub bias: f32,
+    /// Ignored for input nodes
+    pub activation: Activation,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConnectionGene {
+    pub innovation: usize,
+    pub from: usize,
+    pub to: usize,
+    pub weight: f32,
+    pub enabled: bool,
+}
+
+/// A network described by its genes.
+/// Connections never form a cycle, so a genome always compiles into a feed-forward network.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Genome {
+    /// Sorted by id; the inputs come first, then the outputs
+    nodes: Vec<NodeGene>,
+    /// Sorted by innovation number
+    connections: Vec<ConnectionGene>,
+}
+
+impl Genome {
+    /// Initializes a genome without hidden nodes, each input being connected to each output.
+    pub fn minimal(
+        rng: &mut dyn RngCore,
+        innovations: &mut Innovations,
+        inputs: usize,
+        outputs: usize,
+        activation: Activation,
+        initializer: Initializer
+    ) -> Self {
+        innovations.reserve_nodes(inputs + outputs);
+
+        let nodes = (0..inputs)
+            .map(|id| NodeGene { id, kind: NodeKind::Input, bias: 0.0, activation: Activation::Identity })
+            .chain((inputs..inputs + outputs).map(|id| NodeGene {
+                id,
+                kind: NodeKind::Output,
+                bias: initializer.bias(rng, inputs, outputs),
+                activation,
+            }))
+            .collect();
+
+        let mut connections = Vec::new();
+
+        for to in inputs..inputs + outputs {
+            for from in 0..inputs {
+                connections.push(ConnectionGene {
+                    innovation: innovations.connection(from, to),
+                    from,
+                    to,
+                    weight: initializer.weight(rng, inputs, outputs),
+                    enabled: true,
+                });
+            }
+        }
+
+        connections.sort_by_key(|connection| connection.innovation);
+
+        Self { nodes, connections }
+    }
+
+    pub fn nodes(&self) -> &[NodeGene] {
+        &self.nodes
+    }
+
+    pub fn connections(&self) -> &[ConnectionGene] {
+        &self.connections
+    }
+
+    /// Number of input nodes
+    pub fn input_size(&self) -> usize {
+        self.count(NodeKind::Input)
+    }
+
+    /// Number of output nodes
+    pub fn output_size(&self) -> usize {
+        self.count(NodeKind::Output)
+    }
+
+    fn count(&self, kind: NodeKind) -> usize {
+        self.nodes.iter().filter(|node| node.kind == kind).count()
+    }
+
+    fn node(&self, id: usize) -> Option<&NodeGene> {
+        self.nodes
+            .binary_search_by_key(&id, |node| node.id)
+            .ok()
+            .map(|index| &self.nodes[index])
+    }
+
+    /// Splits a random enabled connection in two, through a new hidden node.
+    /// The incoming connection has a weight of one and the outgoing one keeps the old weight,
+    /// so the network barely changes until the new node evolves.
+    /// Returns `false` when there is no enabled connection to split.
+    pub fn mutate_add_node(
+        &mut self,
+        rng: &mut dyn RngCore,
+        innovations: &mut Innovations,
+        activation: Activation
+    ) -> bool {
+        let enabled: Vec<_> = (0..self.connections.len())
+            .filter(|&index| self.connections[index].enabled)
+            .collect();
+
+        if enabled.is_empty() {
+            return false;
+        }
+
+        let index = enabled[rng.gen_range(0..enabled.len())];
+        let old = self.connections[index];
+        let id = innovations.split(old.innovation);
+
+        // The same split may have been disabled and then happened again in another lineage
+        if self.node(id).is_some() {
+            return false;
+        }
+
+        self.connections[index].enabled = false;
+        self.insert_node(NodeGene { id, kind: NodeKind::Hidden, bias: 0.0, activation });
+        self.insert_connection(ConnectionGene {
+            innovation: innovations.connection(old.from, id),
+            from: old.from,
+            to: id,
+            weight: 1.0,
+            enabled: true,
+        });
+        self.insert_connection(ConnectionGene {
+            innovation: innovations.connection(id, old.to),
+            from: id,
+            to: old.to,
+            weight: old.weight,
+            enabled: true,
+        });
+
+        true
+    }
+
+    /// Connects two random nodes that weren't connected yet, with a random weight.
+    /// Returns `false` when every possible connection already exists.
+    pub fn mutate_add_connection(
+        &mut self,
+        rng: &mut dyn RngCore,
+        innovations: &mut Innovations,
+        initializer: Initializer
+    ) -> bool {
+        let mut candidates = Vec::new();
+
+        for from in &self.nodes {
+            for to in &self.nodes {
+                if from.kind != NodeKind::Output
+                    && to.kind != NodeKind::Input
+                    && !self.connects(from.id, to.id)
+                    && !self.reaches(to.id, from.id)
+                {
+                    candidates.push((from.id, to.id));
+                }
+            }
+        }
+
+        if candidates.is_empty() {
+            return false;
+        }
+
+        let (from, to) = candidates[rng.gen_range(0..candidates.len())];
+        let fan_in = self.connections.iter().filter(|connection| connection.to == to).count() + 1;
+
+        self.insert_connection(ConnectionGene {
+            innovation: innovations.connection(from, to),
+            from,
+            to,
+            weight: initializer.weight(rng, fan_in, self.output_size()),
+            enabled: true,
+        });
+
+        true
+    }
+
+    /// Disables a random enabled connection.
+    /// Returns `false` when every connection is already disabled.
+    pub fn mutate_disable_connection(&mut self, rng: &mut dyn RngCore) -> bool {
+        let enabled: Vec<_> = self.connections
+            .iter_mut()
+            .filter(|connection| connection.enabled)
+            .collect();
+
+        if enabled.is_empty() {
+            return false;
+        }
+
+        let index = rng.gen_range(0..enabled.len());
+        enabled.into_iter().nth(index).unwrap().enabled = false;
+
+        true
+    }
+
+    /// Nudges each weight and bias with probability `chance`, by up to `coeff`.
+    pub fn mutate_weights(&mut self, rng: &mut dyn RngCore, chance: f32, coeff: f32) {
+        assert!((0.0..=1.0).contains(&chance));
+
+        let biases = self.nodes
+            .iter_mut()
+            .filter(|node| node.kind != NodeKind::Input)
+            .map(|node| &mut node.bias);
+
+        let weights = self.connections.iter_mut().map(|connection| &mut connection.weight);
+
+        for value in biases.chain(weights) {
+            if rng.gen_bool(chance as _) {
+                *value += coeff * rng.gen_range(-1.0..=1.0);
+            }
+        }
+    }
+
+    /// Mixes two genomes, `fitter` being the parent with the highest fitness.
+    /// Genes found in both parents are inherited from either at random,
+    /// while those found in only one parent are inherited from the fitter one.
+    /// A gene disabled in either parent stays disabled three times out of four.
+    pub fn crossover(rng: &mut dyn RngCore, fitter: &Genome, other: &Genome) -> Genome {
+        let connections = fitter.connections
+            .iter()
+            .map(|gene| {
+                let matching = other.connections
+                    .binary_search_by_key(&gene.innovation, |other| other.innovation)
+                    .ok()
+                    .map(|index| &other.connections[index]);
+
+                let Some(matching) = matching else {
+                    return *gene;
+                };
+
+                let mut child = if rng.gen_bool(0.5) { *gene } else { *matching };
+
+                if !gene.enabled || !matching.enabled {
+                    child.enabled = !rng.gen_bool(0.75);
+                }
+
+                child
+            })
+            .collect();
+
+        let nodes = fitter.nodes
+            .iter()
+            .map(|gene| match other.node(gene.id) {
+                Some(matching) if rng.gen_bool(0.5) => *matching,
+                _ => *gene,
+            })
+            .collect();
+
+        Genome { nodes, connections }
+    }
+
+    /// Turns the genome into a network that can be propagated.
+    pub fn compile(&self) -> CompiledGenome {
+        let index_of: HashMap<_, _> = self.nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| (node.id, index))
+            .collect();
+
+        // Kahn's algorithm: a node is evaluated once all its inputs are
+        let mut pending = vec![0; self.nodes.len()];
+
+        for connection in self.enabled() {
+            pending[index_of[&connection.to]] += 1;
+        }
+
+        let mut ready: Vec<_> = (0..self.nodes.len()).filter(|&index| pending[index] == 0).collect();
+        let mut order = Vec::with_capacity(self.nodes.len());
+
+        while let Some(index) = ready.pop() {
+            order.push(index);
+
+            for connection in self.enabled().filter(|connection| connection.from == self.nodes[index].id) {
+                let to = index_of[&connection.to];
+                pending[to] -= 1;
+
+                if pending[to] == 0 {
+                    ready.push(to);
+                }
+            }
+        }
+
+        let mut nodes = Vec::new();
+        let mut links = Vec::new();
+
+        for index in order {
+            let node = &self.nodes[index];
+
+            if node.kind == NodeKind::Input {
+                continue;
+            }
+
+            let start = links.len();
+
+            links.extend(
+                self.enabled()
+                    .filter(|connection| connection.to == node.id)
+                    .map(|connection| (index_of[&connection.from], connection.weight))
+            );
+
+            nodes.push(CompiledNode {
+                index,
+                bias: node.bias,
+                activation: node.activation,
+                links: start..links.len(),
+            });
+        }
+
+        CompiledGenome {
+            input_size: self.input_size(),
+            output_size: self.output_size(),
+            size: self.nodes.len(),
+            nodes,
+            links,
+        }
+    }
+
+    fn enabled(&self) -> impl Iterator<Item = &ConnectionGene> {
+        self.connections.iter().filter(|connection| connection.enabled)
+    }
+
+    /// Whether a connection (even disabled) goes from node `from` to node `to`.
+    fn connects(&self, from: usize, to: usize) -> bool {
+        self.connections
+            .iter()
+            .any(|connection| connection.from == from && connection.to == to)
+    }
+
+    /// Whether node `to` can be reached from node `from`, following any connection.
+    /// Disabled connections count too, since crossover may enable them again.
+    fn reaches(&self, from: usize, to: usize) -> bool {
+        let mut stack = vec![from];
+        let mut seen = vec![from];
+
+        while let Some(id) = stack.pop() {
+            if id == to {
+                return true;
+            }
+
+            for connection in self.connections.iter().filter(|connection| connection.from == id) {
+                if !seen.contains(&connection.to) {
+                    seen.push(connection.to);
+                    stack.push(connection.to);
+                }
+            }
+        }
+
+        false
+    }
+
+    fn insert_node(&mut self, node: NodeGene) {
+        let index = self.nodes.partition_point(|other| other.id < node.id);
+        self.nodes.insert(index, node);
+    }
+
+    fn insert_connection(&mut self, connection: ConnectionGene) {
+        let index = self.connections
+            .partition_point(|other| other.innovation < connection.innovation);
+
+        self.connections.insert(index, connection);
+    }
+}
+
+/// A genome flattened into a list of nodes in evaluation order.
+#[derive(Clone, Debug)]
+pub struct CompiledGenome {
+    input_size: usize,
+    output_size: usize,
+    /// Number of nodes, inputs included
+    size: usize,
+    /// Every node but the inputs, each one after the nodes it depends on
+    nodes: Vec<CompiledNode>,
+    /// Index of the source node and weight of each enabled connection, grouped by target node
+    links: Vec<(usize, f32)>,
+}
+
+#[derive(Clone, Debug)]
+struct CompiledNode {
+    index: usize,
+    bias: f32,
+    activation: Activation,
+    links: std::ops::Range<usize>,
+}
+
+impl CompiledGenome {
+    /// Panicking version of `try_propagate`.
+    pub fn propagate(&self, inputs: &[f32]) -> Vec<f32> {
+        self.try_propagate(inputs).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Given `inputs`, computes the value of each output node.
+    pub fn try_propagate(&self, inputs: &[f32]) -> Result<Vec<f32>, NetworkError> {
+        if inputs.len() != self.input_size {
+            return Err(NetworkError::InputSizeMismatch {
+                expected: self.input_size,
+                actual: inputs.len()
+            });
+        }
+
+        let mut values = vec![0.0; self.size];
+        values[..self.input_size].copy_from_slice(inputs);
+
+        for node in &self.nodes {
+            let sum = self.links[node.links.clone()]
+                .iter()
+                .map(|&(from, weight)| values[from] * weight)
+                .sum::<f32>();
+
+            values[node.index] = node.activation.apply(sum + node.bias);
+        }
+
+        // Output nodes come right after the inputs
+        Ok(values[self.input_size..self.input_size + self.output_size].to_vec())
+    }
+
+    pub fn input_size(&self) -> usize {
+        self.input_size
+    }
+
+    pub fn output_size(&self) -> usize {
+        self.output_size
+    }
+}
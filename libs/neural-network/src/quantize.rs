@@ -0,0 +1,242 @@
+//! Int8 quantization of trained networks, to ship them at a quarter of their size.
+//!
+//! The weights of each layer are scaled so that the largest one becomes ±127,
+//! then rounded to `i8`; biases and activations stay `f32`.
+
+use crate::*;
+use crate::converted::{self, ConvertedLayer};
+
+impl Network {
+    /// Converts the network into an int8-weight network with one scale factor per layer.
+    /// Recurrent layers carry on from their current state.
+    pub fn quantize(&self) -> QuantizedNetwork {
+        QuantizedNetwork {
+            layers: self.layers.iter().map(QuantizedLayer::new).collect(),
+        }
+    }
+}
+
+/// A network whose weights are stored as `i8`, scaled back to `f32` when propagating.
+/// Over long sequences, the state of recurrent layers may drift away from the float version,
+/// all the more when the float version is itself sensitive to small changes of its weights.
+#[derive(Debug)]
+pub struct QuantizedNetwork {
+    layers: Vec<QuantizedLayer>,
+}
+
+impl QuantizedNetwork {
+    /// Checks that `layers` chain together, like `Network::try_new`.
+    #[cfg(feature = "serde")]
+    pub(crate) fn try_new(layers: Vec<QuantizedLayer>) -> Result<Self, NetworkError> {
+        if layers.is_empty() {
+            return Err(NetworkError::EmptyTopology);
+        }
+
+        for (index, pair) in layers.windows(2).enumerate() {
+            if pair[0].output_size() != pair[1].input_size {
+                return Err(NetworkError::LayerSizeMismatch {
+                    layer: index + 1,
+                    expected: pair[1].input_size,
+                    actual: pair[0].output_size()
+                });
+            }
+        }
+
+        Ok(Self { layers })
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn layers(&self) -> &[QuantizedLayer] {
+        &self.layers
+    }
+
+    /// Panicking version of `try_propagate`.
+    pub fn propagate(&mut self, inputs: &[f32]) -> Vec<f32> {
+        self.try_propagate(inputs).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Given `inputs`, computes the output of the network from its int8 weights,
+    /// which may differ slightly from the output of the original network.
+    pub fn try_propagate(&mut self, inputs: &[f32]) -> Result<Vec<f32>, NetworkError> {
+        converted::try_propagate(&mut self.layers, inputs)
+    }
+
+    /// Propagates each row of `inputs` through both the quantized network and `network`,
+    /// and returns the largest difference between their outputs.
+    /// Recurrent layers of both networks move on to their next state.
+    pub fn max_error<'a>(
+        &mut self,
+        network: &mut Network,
+        inputs: impl IntoIterator<Item = &'a [f32]>
+    ) -> f32 {
+        inputs
+            .into_iter()
+            .flat_map(|inputs| {
+                let expected = network.propagate(inputs.to_vec());
+                let actual = self.propagate(inputs);
+
+                expected
+                    .into_iter()
+                    .zip(actual)
+                    .map(|(expected, actual)| (expected - actual).abs())
+            })
+            .fold(0.0, f32::max)
+    }
+
+    /// Scale factor of each layer: a weight is its quantized value times this factor.
+    pub fn scales(&self) -> Vec<f32> {
+        self.layers.iter().map(|layer| layer.scale).collect()
+    }
+
+    /// Forgets what the recurrent layers remember, like `Network::reset_state`.
+    pub fn reset_state(&mut self) {
+        converted::reset_state(&mut self.layers);
+    }
+}
+
+/// A layer of a `QuantizedNetwork`, whose weights share a single scale factor.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "QuantizedLayerData"))]
+pub(crate) struct QuantizedLayer {
+    input_size: usize,
+    /// Same layout as the weights of `Layer`
+    weights: Vec<i8>,
+    /// Same layout as the recurrent weights of `Layer`, sharing the same scale
+    recurrent_weights: Vec<i8>,
+    scale: f32,
+    biases: Vec<f32>,
+    activation: Activation,
+    state: Vec<f32>,
+}
+
+impl QuantizedLayer {
+    fn new(layer: &Layer) -> Self {
+        let max = layer
+            .weights()
+            .iter()
+            .chain(layer.recurrent_weights())
+            .fold(0.0f32, |max, weight| max.max(weight.abs()));
+
+        // A layer without weights keeps a neutral scale
+        let scale = if max > 0.0 { max / i8::MAX as f32 } else { 1.0 };
+
+        let quantize = |weights: &[f32]| -> Vec<i8> {
+            weights
+                .iter()
+                .map(|weight| (weight / scale).round().clamp(-127.0, 127.0) as i8)
+                .collect()
+        };
+
+        Self {
+            input_size: layer.input_size(),
+            weights: quantize(layer.weights()),
+            recurrent_weights: quantize(layer.recurrent_weights()),
+            scale,
+            biases: layer.biases().to_vec(),
+            activation: layer.activation(),
+            state: layer.state().to_vec(),
+        }
+    }
+
+    fn output_size(&self) -> usize {
+        self.biases.len()
+    }
+}
+
+impl ConvertedLayer for QuantizedLayer {
+    fn input_size(&self) -> usize {
+        self.input_size
+    }
+
+    fn state_mut(&mut self) -> &mut [f32] {
+        &mut self.state
+    }
+
+    fn activations(&self, inputs: &[f32]) -> Vec<f32> {
+        let recurrent_size = self.state.len();
+
+        (0..self.output_size())
+            .map(|neuron| {
+                let row = &self.weights[neuron * self.input_size..(neuron + 1) * self.input_size];
+                let recurrent_row = &self.recurrent_weights[neuron * recurrent_size..(neuron + 1) * recurrent_size];
+
+                let mut sum = inputs
+                    .iter()
+                    .zip(row)
+                    .map(|(input, &weight)| input * weight as f32)
+                    .sum::<f32>();
+
+                for (state, &weight) in self.state.iter().zip(recurrent_row) {
+                    sum += state * weight as f32;
+                }
+
+                self.activation.apply(sum * self.scale + self.biases[neuron])
+            })
+            .collect()
+    }
+}
+
+/// The raw fields of a quantized layer, checked before turning them into a `QuantizedLayer`.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct QuantizedLayerData {
+    input_size: usize,
+    weights: Vec<i8>,
+    recurrent_weights: Vec<i8>,
+    scale: f32,
+    biases: Vec<f32>,
+    activation: Activation,
+    state: Vec<f32>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<QuantizedLayerData> for QuantizedLayer {
+    type Error = String;
+
+    fn try_from(data: QuantizedLayerData) -> Result<Self, Self::Error> {
+        if data.input_size == 0 || data.biases.is_empty() {
+            return Err(NetworkError::EmptyTopology.to_string());
+        }
+
+        let expected = data.input_size * data.biases.len();
+
+        if data.weights.len() != expected {
+            return Err(format!(
+                "a layer of {} neurons with {} inputs needs {} weights, got {}",
+                data.biases.len(), data.input_size, expected, data.weights.len()
+            ));
+        }
+
+        // Dense layers have neither recurrent weights nor state
+        if !data.state.is_empty() && data.state.len() != data.biases.len() {
+            return Err(format!(
+                "a layer of {} neurons can't remember {} values",
+                data.biases.len(), data.state.len()
+            ));
+        }
+
+        let expected = data.state.len() * data.biases.len();
+
+        if data.recurrent_weights.len() != expected {
+            return Err(format!(
+                "a layer of {} neurons remembering {} values needs {} recurrent weights, got {}",
+                data.biases.len(), data.state.len(), expected, data.recurrent_weights.len()
+            ));
+        }
+
+        if !(data.scale.is_finite() && data.scale > 0.0) {
+            return Err(format!("invalid scale {}", data.scale));
+        }
+
+        Ok(Self {
+            input_size: data.input_size,
+            weights: data.weights,
+            recurrent_weights: data.recurrent_weights,
+            scale: data.scale,
+            biases: data.biases,
+            activation: data.activation,
+            state: data.state,
+        })
+    }
+}
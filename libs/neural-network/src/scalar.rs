@@ -0,0 +1,232 @@
+use std::fmt::Debug;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub};
+
+/// A number that networks can be made of.
+/// Implemented for `f32` (the default), `f64`, and the fixed-point `Fixed`.
+pub trait Scalar:
+    Copy
+    + Debug
+    + Default
+    + PartialEq
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + Sum
+    + 'static
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// Converts from `f32`, rounding to the nearest representable value.
+    fn from_f32(value: f32) -> Self;
+
+    /// Converts to `f32`, rounding to the nearest representable value.
+    fn to_f32(self) -> f32;
+
+    fn abs(self) -> Self;
+
+    fn exp(self) -> Self;
+
+    fn tanh(self) -> Self;
+}
+
+impl Scalar for f32 {
+    const ZERO: Self = 0.0;
+    const ONE: Self = 1.0;
+
+    fn from_f32(value: f32) -> Self {
+        value
+    }
+
+    fn to_f32(self) -> f32 {
+        self
+    }
+
+    fn abs(self) -> Self {
+        f32::abs(self)
+    }
+
+    fn exp(self) -> Self {
+        f32::exp(self)
+    }
+
+    fn tanh(self) -> Self {
+        f32::tanh(self)
+    }
+}
+
+impl Scalar for f64 {
+    const ZERO: Self = 0.0;
+    const ONE: Self = 1.0;
+
+    fn from_f32(value: f32) -> Self {
+        value as f64
+    }
+
+    fn to_f32(self) -> f32 {
+        self as f32
+    }
+
+    fn abs(self) -> Self {
+        f64::abs(self)
+    }
+
+    fn exp(self) -> Self {
+        f64::exp(self)
+    }
+
+    fn tanh(self) -> Self {
+        f64::tanh(self)
+    }
+}
+
+/// A signed Q16.16 fixed-point number, saturating instead of overflowing.
+/// Only integer arithmetic is involved, even for `exp` and `tanh`,
+/// so the same network gives bit-identical outputs on every platform.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fixed(i32);
+
+impl Fixed {
+    pub const FRAC_BITS: u32 = 16;
+    pub const MAX: Self = Self(i32::MAX);
+    pub const MIN: Self = Self(i32::MIN);
+
+    const ONE_BITS: i64 = 1 << Self::FRAC_BITS;
+    /// `ln(2)`, in Q16.16
+    const LN_2: i64 = 45_426;
+
+    /// Builds a number from its raw Q16.16 representation.
+    pub const fn from_bits(bits: i32) -> Self {
+        Self(bits)
+    }
+
+    /// Raw Q16.16 representation of the number
+    pub const fn to_bits(self) -> i32 {
+        self.0
+    }
+
+    fn saturate(bits: i64) -> Self {
+        Self(bits.clamp(i32::MIN as i64, i32::MAX as i64) as i32)
+    }
+}
+
+impl Add for Fixed {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0.saturating_add(other.0))
+    }
+}
+
+impl AddAssign for Fixed {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self(self.0.saturating_sub(other.0))
+    }
+}
+
+impl Mul for Fixed {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self::saturate((self.0 as i64 * other.0 as i64) >> Self::FRAC_BITS)
+    }
+}
+
+impl Div for Fixed {
+    type Output = Self;
+
+    /// Dividing by zero saturates towards the sign of the dividend.
+    fn div(self, other: Self) -> Self {
+        if other.0 == 0 {
+            return if self.0 >= 0 { Self::MAX } else { Self::MIN };
+        }
+
+        Self::saturate(((self.0 as i64) << Self::FRAC_BITS) / other.0 as i64)
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(self.0.saturating_neg())
+    }
+}
+
+impl Sum for Fixed {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, Add::add)
+    }
+}
+
+impl Scalar for Fixed {
+    const ZERO: Self = Self(0);
+    const ONE: Self = Self(1 << Self::FRAC_BITS);
+
+    fn from_f32(value: f32) -> Self {
+        // `as` saturates, and maps NaN to zero
+        Self((value * Self::ONE_BITS as f32).round() as i32)
+    }
+
+    fn to_f32(self) -> f32 {
+        self.0 as f32 / Self::ONE_BITS as f32
+    }
+
+    fn abs(self) -> Self {
+        Self(self.0.saturating_abs())
+    }
+
+    /// Writes `x` as `k * ln(2) + r`, with `|r| <= ln(2) / 2`,
+    /// so that `exp(x) = 2^k * exp(r)` where `exp(r)` is a short Taylor series.
+    fn exp(self) -> Self {
+        let x = self.0 as i64;
+
+        // Beyond these bounds, the result doesn't fit or rounds to zero
+        if x >= 681_391 {
+            return Self::MAX;
+        }
+
+        if x <= -772_243 {
+            return Self::ZERO;
+        }
+
+        let k = (x + x.signum() * Self::LN_2 / 2) / Self::LN_2;
+        let r = x - k * Self::LN_2;
+
+        let mut term = Self::ONE_BITS;
+        let mut sum = Self::ONE_BITS;
+
+        for n in 1..=6 {
+            term = term * r / (n * Self::ONE_BITS);
+            sum += term;
+        }
+
+        if k >= 0 {
+            Self::saturate(sum << k)
+        } else {
+            Self::saturate(sum >> -k)
+        }
+    }
+
+    /// `1 - 2 / (exp(2x) + 1)` for positive `x`, mirrored for negative ones.
+    fn tanh(self) -> Self {
+        let x = self.abs();
+        let y = Self::ONE - Self(2 << Self::FRAC_BITS) / ((x + x).exp() + Self::ONE);
+
+        if self.0 < 0 { -y } else { y }
+    }
+}
@@ -0,0 +1,184 @@
+//! Pruning of weak connections, and networks that only store the connections left.
+
+use crate::*;
+use crate::converted::{self, ConvertedLayer};
+
+/// How many connections (biases excluded) survived a pruning.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PruneReport {
+    /// Connections of the network, recurrent ones included
+    pub connections: usize,
+    /// Connections whose weight isn't zero
+    pub survivors: usize,
+}
+
+impl PruneReport {
+    /// Share of the connections that survived, between 0.0 and 1.0.
+    pub fn survival_rate(&self) -> f32 {
+        if self.connections == 0 {
+            return 1.0;
+        }
+
+        self.survivors as f32 / self.connections as f32
+    }
+}
+
+impl Network {
+    /// Zeroes every weight whose magnitude is below `threshold`.
+    /// Biases are left untouched.
+    pub fn prune(&mut self, threshold: f32) -> PruneReport {
+        let mut report = PruneReport { connections: 0, survivors: 0 };
+
+        for layer in &mut self.layers {
+            let weights = layer.weights.iter_mut().chain(&mut layer.recurrent_weights);
+
+            for weight in weights {
+                if weight.abs() < threshold {
+                    *weight = 0.0;
+                }
+
+                report.connections += 1;
+                report.survivors += (*weight != 0.0) as usize;
+            }
+        }
+
+        report
+    }
+
+    /// Copies the network, keeping only the connections whose weight isn't zero.
+    /// Recurrent layers carry on from their current state.
+    pub fn to_sparse(&self) -> SparseNetwork {
+        SparseNetwork {
+            layers: self.layers.iter().map(SparseLayer::new).collect(),
+        }
+    }
+}
+
+/// A network which skips its zeroed connections when propagating,
+/// which pays off once most of them were pruned.
+#[derive(Debug)]
+pub struct SparseNetwork {
+    layers: Vec<SparseLayer>,
+}
+
+impl SparseNetwork {
+    /// Panicking version of `try_propagate`.
+    pub fn propagate(&mut self, inputs: &[f32]) -> Vec<f32> {
+        self.try_propagate(inputs).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Given `inputs`, computes the output of the network,
+    /// only going through the connections left after pruning.
+    pub fn try_propagate(&mut self, inputs: &[f32]) -> Result<Vec<f32>, NetworkError> {
+        converted::try_propagate(&mut self.layers, inputs)
+    }
+
+    /// Number of connections left, recurrent ones included.
+    pub fn connections(&self) -> usize {
+        self.layers
+            .iter()
+            .map(|layer| layer.weights.len() + layer.recurrent_weights.len())
+            .sum()
+    }
+
+    /// Forgets what the recurrent layers remember.
+    pub fn reset_state(&mut self) {
+        converted::reset_state(&mut self.layers);
+    }
+}
+
+/// The non-zero weights of a layer, stored row by row:
+/// the weights of neuron `o` are `weights[offsets[o]..offsets[o + 1]]`,
+/// and connect it to the inputs (or neurons, when recurrent) listed in `indices`.
+#[derive(Debug, Default)]
+struct SparseWeights {
+    offsets: Vec<usize>,
+    indices: Vec<usize>,
+    weights: Vec<f32>,
+}
+
+impl SparseWeights {
+    fn new<'a>(rows: impl Iterator<Item = &'a [f32]>) -> Self {
+        let mut sparse = Self { offsets: vec![0], ..Self::default() };
+
+        for row in rows {
+            for (index, &weight) in row.iter().enumerate() {
+                if weight != 0.0 {
+                    sparse.indices.push(index);
+                    sparse.weights.push(weight);
+                }
+            }
+
+            sparse.offsets.push(sparse.weights.len());
+        }
+
+        sparse
+    }
+
+    fn len(&self) -> usize {
+        self.weights.len()
+    }
+
+    /// Weighted sum of `values` for neuron `neuron`.
+    fn dot(&self, neuron: usize, value: impl Fn(usize) -> f32) -> f32 {
+        let range = self.offsets[neuron]..self.offsets[neuron + 1];
+
+        self.indices[range.clone()]
+            .iter()
+            .zip(&self.weights[range])
+            .map(|(&index, weight)| value(index) * weight)
+            .sum()
+    }
+}
+
+/// A layer of a `SparseNetwork`, with the non-zero weights of its inputs
+/// and of its previous outputs, stored apart.
+#[derive(Debug)]
+struct SparseLayer {
+    input_size: usize,
+    weights: SparseWeights,
+    biases: Vec<f32>,
+    activation: Activation,
+    recurrent_weights: SparseWeights,
+    state: Vec<f32>,
+}
+
+impl SparseLayer {
+    fn new(layer: &Layer) -> Self {
+        let neurons = 0..layer.output_size();
+
+        Self {
+            input_size: layer.input_size(),
+            weights: SparseWeights::new(neurons.clone().map(|neuron| layer.row(neuron))),
+            biases: layer.biases().to_vec(),
+            activation: layer.activation(),
+            recurrent_weights: SparseWeights::new(neurons.map(|neuron| layer.recurrent_row(neuron))),
+            state: layer.state().to_vec(),
+        }
+    }
+
+    fn output_size(&self) -> usize {
+        self.biases.len()
+    }
+}
+
+impl ConvertedLayer for SparseLayer {
+    fn input_size(&self) -> usize {
+        self.input_size
+    }
+
+    fn state_mut(&mut self) -> &mut [f32] {
+        &mut self.state
+    }
+
+    fn activations(&self, inputs: &[f32]) -> Vec<f32> {
+        (0..self.output_size())
+            .map(|neuron| {
+                let output = self.weights.dot(neuron, |index| inputs[index])
+                    + self.recurrent_weights.dot(neuron, |index| self.state[index]);
+
+                self.activation.apply(output + self.biases[neuron])
+            })
+            .collect()
+    }
+}
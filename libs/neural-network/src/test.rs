@@ -5,6 +5,33 @@ use crate::*;
 mod tests {
     use super::*;
 
+    /// Topology shared by the tests: 3 inputs, a recurrent hidden layer of 4 neurons and 2 outputs.
+    fn topology() -> [LayerTopology; 3] {
+        [
+            LayerTopology { neurons: 3, activation: Activation::ReLU, kind: LayerKind::Dense },
+            LayerTopology { neurons: 4, activation: Activation::Tanh, kind: LayerKind::Recurrent },
+            LayerTopology { neurons: 2, activation: Activation::Identity, kind: LayerKind::Dense },
+        ]
+    }
+
+    /// The 42 weights of a network of `topology()`, spread over `-1.0..=1.0` with a few zeros.
+    /// Shifting them by `offset` gives another network of the same topology.
+    fn weights(offset: usize) -> impl Iterator<Item = f32> {
+        (0..42).map(move |n| (((n + offset) * 7) % 11) as f32 / 5.0 - 1.0)
+    }
+
+    /// The network of `topology()` shared by the tests.
+    fn network() -> Network {
+        Network::from_weights(&topology(), weights(0))
+    }
+
+    /// Networks of `topology()`, each with its own weights.
+    fn networks(count: usize) -> Vec<Network> {
+        (0..count)
+            .map(|offset| Network::from_weights(&topology(), weights(offset)))
+            .collect()
+    }
+
     mod random {
         use super::*;
         use rand::SeedableRng;
@@ -28,26 +55,1413 @@ mod tests {
         }
     }
 
-    mod propagate {
+    mod network_random {
         use super::*;
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha8Rng;
 
         #[test]
         fn test() {
-            let neuron = Neuron {
-                bias: 0.5,
-                weights: vec![-0.3, 0.8]
-            };
+            let layers = &[
+                LayerTopology { neurons: 3, activation: Activation::ReLU, kind: LayerKind::Dense },
+                LayerTopology { neurons: 2, activation: Activation::Tanh, kind: LayerKind::Dense },
+            ];
+
+            let network_a = Network::random(
+                &mut ChaCha8Rng::from_seed(Default::default()),
+                layers,
+                Initializer::default()
+            );
+            let network_b = Network::random(
+                &mut ChaCha8Rng::from_seed(Default::default()),
+                layers,
+                Initializer::default()
+            );
+
+            let weights = network_a.weights();
+            assert_eq!(weights, network_b.weights());
+
+            // Draws in the same order as `Neuron::random`
+            approx::assert_relative_eq!(
+                &weights[..4],
+                [-0.6255188, 0.67383957, 0.8181262, 0.26284897].as_ref()
+            );
+        }
+    }
+
+    mod initializer {
+        use super::*;
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha8Rng;
+
+        fn weights(initializer: Initializer) -> Vec<f32> {
+            let layers = &[
+                LayerTopology { neurons: 50, activation: Activation::ReLU, kind: LayerKind::Dense },
+                LayerTopology { neurons: 40, activation: Activation::ReLU, kind: LayerKind::Dense },
+            ];
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            Network::random(&mut rng, layers, initializer).weights()
+        }
+
+        /// Splits the weights of a 50 → 40 network into its biases and its other weights.
+        fn split(weights: &[f32]) -> (Vec<f32>, Vec<f32>) {
+            let biases = weights.iter().step_by(51).copied().collect();
+            let others = weights
+                .chunks(51)
+                .flat_map(|neuron| neuron[1..].iter().copied())
+                .collect();
+
+            (biases, others)
+        }
+
+        fn std_dev(values: &[f32]) -> f32 {
+            let mean = values.iter().sum::<f32>() / values.len() as f32;
+            let variance = values.iter().map(|value| (value - mean).powi(2)).sum::<f32>();
+
+            (variance / values.len() as f32).sqrt()
+        }
+
+        #[test]
+        fn uniform() {
+            let weights = weights(Initializer::Uniform { low: 0.25, high: 0.5 });
+
+            assert!(weights.iter().all(|weight| (0.25..=0.5).contains(weight)));
+        }
+
+        #[test]
+        fn xavier() {
+            let limit = (6.0f32 / 90.0).sqrt();
+            let (biases, weights) = split(&weights(Initializer::Xavier));
+
+            assert!(biases.iter().all(|&bias| bias == 0.0));
+            assert!(weights.iter().all(|weight| weight.abs() <= limit));
+            approx::assert_relative_eq!(std_dev(&weights), limit / 3.0f32.sqrt(), epsilon = 0.01);
+        }
+
+        #[test]
+        fn he() {
+            let (biases, weights) = split(&weights(Initializer::He));
+
+            assert!(biases.iter().all(|&bias| bias == 0.0));
+            approx::assert_relative_eq!(std_dev(&weights), (2.0f32 / 50.0).sqrt(), epsilon = 0.01);
+        }
+
+        #[test]
+        fn normal() {
+            let weights = weights(Initializer::Normal(0.5));
+
+            approx::assert_relative_eq!(std_dev(&weights), 0.5, epsilon = 0.02);
+        }
+
+        #[test]
+        fn zeros() {
+            assert!(weights(Initializer::Zeros).iter().all(|&weight| weight == 0.0));
+        }
+
+        #[test]
+        fn recurrent_fan_in() {
+            // The recurrent weights count as inputs: 2 inputs + 4 recurrent ones
+            let layers = &[
+                LayerTopology { neurons: 2, activation: Activation::Tanh, kind: LayerKind::Dense },
+                LayerTopology { neurons: 4, activation: Activation::Tanh, kind: LayerKind::Recurrent },
+            ];
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let network = Network::random(&mut rng, layers, Initializer::Xavier);
+            let limit = (6.0f32 / 10.0).sqrt();
+
+            assert!(network.weights().iter().all(|weight| weight.abs() <= limit));
+        }
+
+        #[test]
+        fn invalid() {
+            let layers = &[
+                LayerTopology { neurons: 3, activation: Activation::ReLU, kind: LayerKind::Dense },
+                LayerTopology { neurons: 2, activation: Activation::ReLU, kind: LayerKind::Dense },
+            ];
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            for initializer in [Initializer::Uniform { low: 1.0, high: -1.0 }, Initializer::Normal(-0.5)] {
+                let err = Network::try_random(&mut rng, layers, initializer).unwrap_err();
+
+                assert_eq!(err, NetworkError::InvalidInitializer);
+            }
+        }
+    }
+
+    mod propagate {
+        use super::*;
+
+        fn propagate(inputs: &[f32], activation: Activation) -> f32 {
+            let mut layer = Layer::new(
+                vec![Neuron::new(0.5, vec![-0.3, 0.8])],
+                activation
+            );
+
+            let mut outputs = Vec::new();
+            layer.propagate_into(inputs, &mut outputs);
+            outputs[0]
+        }
 
+        #[test]
+        fn test() {
             // Makes sure that ReLU works
             approx::assert_relative_eq!(
-                neuron.propagate(&[-10.0, -10.0]), 
+                propagate(&[-10.0, -10.0], Activation::ReLU), 
                 0.0
             );
 
             approx::assert_relative_eq!(
-                neuron.propagate(&[0.5, 1.0]),
+                propagate(&[0.5, 1.0], Activation::ReLU),
                 (-0.3 * 0.5) + (0.8 * 1.0) + 0.5
             );
+
+            // Makes sure that negative outputs are kept when asked to
+            approx::assert_relative_eq!(
+                propagate(&[-10.0, -10.0], Activation::Identity),
+                (-0.3 * -10.0) + (0.8 * -10.0) + 0.5
+            );
+        }
+    }
+
+    mod activation {
+        use super::*;
+
+        fn check(activation: Activation, expected: [f32; 3]) {
+            let actual: Vec<_> = [-2.0, 0.0, 2.0]
+                .iter()
+                .map(|&x| activation.apply(x))
+                .collect();
+
+            approx::assert_relative_eq!(
+                actual.as_slice(),
+                expected.as_ref()
+            );
+        }
+
+        #[test]
+        fn test() {
+            check(Activation::ReLU, [0.0, 0.0, 2.0]);
+            check(Activation::LeakyReLU(0.1), [-0.2, 0.0, 2.0]);
+            check(Activation::Sigmoid, [0.11920292, 0.5, 0.880797]);
+            check(Activation::Tanh, [-0.9640276, 0.0, 0.9640276]);
+            check(Activation::Identity, [-2.0, 0.0, 2.0]);
+            check(Activation::Softsign, [-2.0 / 3.0, 0.0, 2.0 / 3.0]);
+            check(Activation::Step, [0.0, 0.0, 1.0]);
+        }
+    }
+
+    mod network_propagate {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut network = Network::new(vec![
+                Layer::new(
+                    vec![Neuron::new(0.0, vec![1.0]), Neuron::new(0.0, vec![-1.0])],
+                    Activation::ReLU
+                ),
+                Layer::new(
+                    vec![Neuron::new(0.0, vec![-1.0, 0.5])],
+                    Activation::Identity
+                )
+            ]);
+
+            // The output layer is allowed to be negative
+            approx::assert_relative_eq!(network.propagate(vec![2.0])[0], -2.0);
+            approx::assert_relative_eq!(network.propagate(vec![-2.0])[0], 1.0);
+        }
+    }
+
+    mod propagate_into {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut network = network();
+            let mut other = network.clone();
+
+            let mut workspace = Workspace::new();
+
+            for inputs in [[0.1, -0.4, 0.9], [1.0, 0.5, -2.0]] {
+                let expected = other.propagate(inputs.to_vec());
+                let actual = network.propagate_into(&inputs, &mut workspace);
+
+                assert_eq!(actual, expected.as_slice());
+            }
+        }
+    }
+
+    mod propagate_traced {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut network = Network::new(vec![
+                Layer::new(
+                    vec![Neuron::new(0.5, vec![1.0]), Neuron::new(0.0, vec![-1.0])],
+                    Activation::ReLU
+                ),
+                Layer::new(
+                    vec![Neuron::new(0.0, vec![-1.0, 0.5])],
+                    Activation::Identity
+                )
+            ]);
+
+            let trace = network.propagate_traced(&[2.0]);
+
+            assert_eq!(trace.layers.len(), 2);
+            assert_eq!(trace.layers[0].pre_activations, [2.5, -2.0]);
+            assert_eq!(trace.layers[0].activations, [2.5, 0.0]);
+            assert_eq!(trace.layers[1].pre_activations, [-2.5]);
+            assert_eq!(trace.layers[1].activations, [-2.5]);
+            assert_eq!(trace.outputs(), network.propagate(vec![2.0]).as_slice());
+        }
+
+        #[test]
+        fn recurrent() {
+            let mut network = network();
+            let mut other = network.clone();
+
+            // Tracing moves the state forward, just like a regular propagation
+            for _ in 0..3 {
+                let trace = network.propagate_traced(&[1.0, 0.5, -0.5]);
+                let expected = other.propagate(vec![1.0, 0.5, -0.5]);
+
+                assert_eq!(trace.outputs(), expected.as_slice());
+            }
+        }
+
+        #[test]
+        #[should_panic(expected = "expected 1 inputs, got 2")]
+        fn input_size_mismatch() {
+            let mut network = Network::new(vec![
+                Layer::new(vec![Neuron::new(0.0, vec![1.0])], Activation::ReLU),
+                Layer::new(vec![Neuron::new(0.0, vec![1.0])], Activation::ReLU),
+            ]);
+
+            network.propagate_traced(&[1.0, 2.0]);
+        }
+    }
+
+    mod propagate_batch {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut network = network();
+            network.propagate(vec![1.0, 0.5, -0.5]);
+
+            let inputs = [0.1, -0.4, 0.9, 1.0, 0.5, -2.0];
+            let expected: Vec<_> = inputs
+                .chunks(3)
+                .flat_map(|inputs| network.clone().propagate(inputs.to_vec()))
+                .collect();
+
+            let actual = network.propagate_batch(&inputs);
+
+            approx::assert_relative_eq!(
+                actual.as_slice(),
+                expected.as_slice()
+            );
+        }
+
+        #[test]
+        fn samples_are_independent() {
+            let mut network = network();
+            network.propagate(vec![1.0, 0.5, -0.5]);
+
+            let state = network.layers()[0].state().to_vec();
+            let outputs = network.propagate_batch(&[0.1, -0.4, 0.9, 0.1, -0.4, 0.9]);
+
+            // The same sample twice gives the same outputs, and the state is kept
+            assert_eq!(outputs[..2], outputs[2..]);
+            assert_eq!(network.layers()[0].state(), state);
+        }
+    }
+
+    mod network_batch {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut networks = networks(4);
+
+            let mut batch = NetworkBatch::new(&networks);
+            assert_eq!(batch.len(), 4);
+
+            let inputs = [0.1, -0.4, 0.9, 1.0, 0.5, -2.0, 0.0, 0.3, 0.7, -0.1, 0.2, 0.6];
+            let mut workspace = Workspace::new();
+
+            for _ in 0..2 {
+                let expected: Vec<_> = networks
+                    .iter_mut()
+                    .zip(inputs.chunks(3))
+                    .flat_map(|(network, inputs)| network.propagate(inputs.to_vec()))
+                    .collect();
+
+                approx::assert_relative_eq!(
+                    batch.propagate_into(&inputs, &mut workspace),
+                    expected.as_slice()
+                );
+            }
+        }
+
+        #[test]
+        fn empty() {
+            let mut batch = NetworkBatch::new(&[]);
+
+            assert!(batch.is_empty());
+            assert_eq!(batch.propagate(&[]), Vec::<f32>::new());
+        }
+    }
+
+    mod errors {
+        use super::*;
+
+        #[test]
+        fn too_few_weights() {
+            let result = Network::try_from_weights(&topology(), vec![0.5; 41]);
+
+            assert_eq!(
+                result.unwrap_err(),
+                NetworkError::TooFewWeights { expected: 42, actual: 41 }
+            );
+        }
+
+        #[test]
+        fn too_many_weights() {
+            let result = Network::try_from_weights(&topology(), vec![0.5; 43]);
+
+            assert_eq!(
+                result.unwrap_err(),
+                NetworkError::TooManyWeights { expected: 42, actual: 43 }
+            );
+        }
+
+        #[test]
+        fn empty_topology() {
+            let layers = [LayerTopology { neurons: 3, activation: Activation::ReLU, kind: LayerKind::Dense }];
+
+            assert_eq!(
+                Network::<f32>::try_from_weights(&layers, vec![]).unwrap_err(),
+                NetworkError::EmptyTopology
+            );
+
+            let layers = [
+                LayerTopology { neurons: 3, activation: Activation::ReLU, kind: LayerKind::Dense },
+                LayerTopology { neurons: 0, activation: Activation::ReLU, kind: LayerKind::Dense },
+            ];
+
+            assert_eq!(
+                Network::<f32>::try_from_weights(&layers, vec![]).unwrap_err(),
+                NetworkError::EmptyTopology
+            );
+        }
+
+        #[test]
+        fn no_layers() {
+            assert_eq!(Network::<f32>::try_new(vec![]).unwrap_err(), NetworkError::EmptyTopology);
+        }
+
+        #[test]
+        fn input_size_mismatch() {
+            let mut network = network();
+
+            assert_eq!(
+                network.try_propagate(vec![1.0, 2.0]).unwrap_err(),
+                NetworkError::InputSizeMismatch { expected: 3, actual: 2 }
+            );
+        }
+
+        #[test]
+        fn layer_size_mismatch() {
+            let result = Network::try_new(vec![
+                Layer::new(vec![Neuron::new(0.1, vec![0.2, 0.3])], Activation::ReLU),
+                Layer::new(vec![Neuron::new(0.4, vec![0.5, 0.6])], Activation::ReLU),
+            ]);
+
+            assert_eq!(
+                result.unwrap_err(),
+                NetworkError::LayerSizeMismatch { layer: 1, expected: 2, actual: 1 }
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "too many weights were given")]
+        fn panicking_version() {
+            Network::from_weights(&topology(), vec![0.5; 43]);
+        }
+    }
+
+    mod recurrent {
+        use super::*;
+
+        #[test]
+        fn weights_layout() {
+            let weights: Vec<_> = (0..42).map(|n| n as f32).collect();
+            let network = Network::from_weights(&topology(), weights.clone());
+
+            assert_eq!(LayerTopology::weights_count(&topology()), 42);
+            assert_eq!(network.weights(), weights);
+
+            // The first neuron holds its bias, 3 input weights, then 4 recurrent weights
+            let layer = &network.layers()[0];
+            assert_eq!(layer.biases()[0], 0.0);
+            assert_eq!(layer.weights()[..3], [1.0, 2.0, 3.0]);
+            assert_eq!(layer.recurrent_weights()[..4], [4.0, 5.0, 6.0, 7.0]);
+        }
+
+        #[test]
+        fn remembers_previous_inputs() {
+            let mut network = network();
+
+            let first = network.propagate(vec![1.0, 0.5, -0.5]);
+            let second = network.propagate(vec![1.0, 0.5, -0.5]);
+            assert_ne!(first, second);
+
+            network.reset_state();
+            assert_eq!(network.propagate(vec![1.0, 0.5, -0.5]), first);
+        }
+
+        #[test]
+        fn batch() {
+            let mut networks = networks(3);
+            let mut batch = NetworkBatch::new(&networks);
+            let inputs = [1.0, 0.5, -0.5, -0.3, 0.8, 0.1, 0.0, 2.0, -1.0];
+
+            for _ in 0..3 {
+                let expected: Vec<_> = networks
+                    .iter_mut()
+                    .zip(inputs.chunks(3))
+                    .flat_map(|(network, inputs)| network.propagate(inputs.to_vec()))
+                    .collect();
+                let actual = batch.propagate(&inputs);
+
+                approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+            }
+        }
+
+        #[test]
+        fn batch_stores_state() {
+            let mut networks = networks(3);
+            let mut expected = networks.clone();
+            let mut batch = NetworkBatch::new(&networks);
+            let inputs = [1.0, 0.5, -0.5, -0.3, 0.8, 0.1, 0.0, 2.0, -1.0];
+
+            for (network, inputs) in expected.iter_mut().zip(inputs.chunks(3)) {
+                network.propagate(inputs.to_vec());
+            }
+
+            batch.propagate(&inputs);
+            batch.store_state(&mut networks);
+
+            for (actual, expected) in networks.iter().zip(&expected) {
+                approx::assert_relative_eq!(actual.layers()[0].state(), expected.layers()[0].state());
+                assert_ne!(actual.layers()[0].state(), [0.0; 4]);
+            }
+        }
+
+        /// The state lives in the network itself, so it can be shared across threads.
+        #[test]
+        fn network_is_sync() {
+            fn assert_sync<T: Sync>() {}
+
+            assert_sync::<Network>();
+            assert_sync::<NetworkBatch>();
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    mod serde {
+        use super::*;
+
+        /// The shared network, with an activation function which has a parameter.
+        fn network() -> Network {
+            let mut layers = topology();
+            layers[2].activation = Activation::LeakyReLU(0.1);
+
+            Network::from_weights(&layers, weights(0))
+        }
+
+        fn assert_same(actual: &Network, expected: &Network) {
+            assert_eq!(actual.weights(), expected.weights());
+
+            for (actual, expected) in actual.layers().iter().zip(expected.layers()) {
+                assert_eq!(actual.input_size(), expected.input_size());
+                assert_eq!(actual.activation(), expected.activation());
+                assert_eq!(actual.kind(), expected.kind());
+            }
+        }
+
+        #[test]
+        fn json_round_trip() {
+            let json = serde_json::to_string(&network()).unwrap();
+            let actual: Network = serde_json::from_str(&json).unwrap();
+
+            assert!(json.starts_with(r#"{"format":"lib-neural-network","version":2,"#));
+            assert_same(&actual, &network());
+        }
+
+        #[test]
+        fn without_hidden_layer() {
+            let layers = [topology()[0], topology()[2]];
+            let mut expected = Network::from_weights(&layers, weights(0).take(8));
+
+            let json = serde_json::to_string(&expected).unwrap();
+            let mut actual: Network = serde_json::from_str(&json).unwrap();
+
+            assert_same(&actual, &expected);
+            assert_eq!(actual.propagate(vec![1.0, 0.5, -0.5]), expected.propagate(vec![1.0, 0.5, -0.5]));
+        }
+
+        #[test]
+        fn bincode_round_trip() {
+            let bytes = bincode::serialize(&network()).unwrap();
+            let actual: Network = bincode::deserialize(&bytes).unwrap();
+
+            assert_same(&actual, &network());
+        }
+
+        #[test]
+        fn other_version_fails() {
+            let json = serde_json::to_string(&network())
+                .unwrap()
+                .replace(r#""version":2"#, r#""version":0"#);
+            let error = serde_json::from_str::<Network>(&json).unwrap_err();
+
+            assert!(error.to_string().contains("unsupported network format version 0"));
+        }
+
+        #[test]
+        fn version_1_is_still_read() {
+            let json = r#"{
+                "format": "lib-neural-network",
+                "version": 1,
+                "layers": [
+                    {"input_size": 1, "weights": [0.5], "biases": [0.1], "activation": "ReLU"},
+                    {"input_size": 1, "weights": [2.0], "biases": [0.0], "activation": "Identity"}
+                ]
+            }"#;
+            let mut network: Network = serde_json::from_str(json).unwrap();
+
+            assert_eq!(network.layers()[0].kind(), LayerKind::Dense);
+            approx::assert_relative_eq!(network.propagate(vec![1.0])[0], 1.2);
+        }
+
+        /// One input, a ReLU neuron (bias 0.1, weight 0.5) and an identity output (weight 2.0),
+        /// as version 1 saved it with bincode.
+        const VERSION_1_BINCODE: &[u8] = &[
+            // Header
+            18, 0, 0, 0, 0, 0, 0, 0, 108, 105, 98, 45, 110, 101, 117, 114, 97, 108, 45, 110, 101,
+            116, 119, 111, 114, 107, 1, 0, 0, 0,
+            // Two layers, with their input size, weights, biases and activation
+            2, 0, 0, 0, 0, 0, 0, 0,
+            1, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 63, 1, 0, 0, 0, 0, 0, 0, 0,
+            205, 204, 204, 61, 0, 0, 0, 0,
+            1, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 64, 1, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 4, 0, 0, 0,
+        ];
+
+        #[test]
+        fn version_1_is_still_read_from_bincode() {
+            let mut network: Network = bincode::deserialize(VERSION_1_BINCODE).unwrap();
+
+            assert_eq!(network.layers()[0].kind(), LayerKind::Dense);
+            assert_eq!(network.layers()[1].activation(), Activation::Identity);
+            approx::assert_relative_eq!(network.propagate(vec![1.0])[0], 1.2);
+        }
+
+        #[test]
+        fn version_2_needs_the_kind_of_layers() {
+            let json = serde_json::to_string(&network())
+                .unwrap()
+                .replace(r#""kind":"Recurrent","#, "");
+            let error = serde_json::from_str::<Network>(&json).unwrap_err();
+
+            assert!(error.to_string().contains("missing field `kind`"));
+        }
+
+        #[test]
+        fn missing_header_fails() {
+            let json = r#"{"layers":[]}"#;
+
+            assert!(serde_json::from_str::<Network>(json).is_err());
+        }
+
+        #[test]
+        fn wrong_weights_count_fails() {
+            let mut json = serde_json::to_value(network()).unwrap();
+            json["layers"][0]["biases"]
+                .as_array_mut()
+                .unwrap()
+                .push(0.0.into());
+
+            let error = serde_json::from_value::<Network>(json).unwrap_err();
+
+            assert!(error.to_string().contains("needs 15 weights, got 12"));
+        }
+    }
+
+    mod neat {
+        use super::*;
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha8Rng;
+
+        const HALF: Initializer = Initializer::Uniform { low: 0.5, high: 0.5 };
+
+        fn genome(rng: &mut dyn RngCore, innovations: &mut Innovations) -> Genome {
+            Genome::minimal(rng, innovations, 2, 1, Activation::Identity, HALF)
+        }
+
+        #[test]
+        fn minimal() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let genome = genome(&mut rng, &mut Innovations::new());
+
+            assert_eq!(genome.input_size(), 2);
+            assert_eq!(genome.output_size(), 1);
+            assert_eq!(genome.connections().len(), 2);
+
+            let network = genome.compile();
+            approx::assert_relative_eq!(network.propagate(&[1.0, 3.0])[0], 2.5);
+        }
+
+        #[test]
+        fn add_node() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut innovations = Innovations::new();
+            let mut genome = genome(&mut rng, &mut innovations);
+
+            assert!(genome.mutate_add_node(&mut rng, &mut innovations, Activation::Identity));
+
+            assert_eq!(genome.nodes().len(), 4);
+            assert_eq!(genome.nodes()[3].kind, NodeKind::Hidden);
+            assert_eq!(genome.connections().len(), 4);
+            assert_eq!(genome.connections().iter().filter(|gene| !gene.enabled).count(), 1);
+
+            // The split connection behaves like the one it replaces
+            let network = genome.compile();
+            approx::assert_relative_eq!(network.propagate(&[1.0, 3.0])[0], 2.5);
+        }
+
+        #[test]
+        fn same_mutation_same_innovations() {
+            let mut innovations = Innovations::new();
+            let mut rng_a = ChaCha8Rng::from_seed(Default::default());
+            let mut rng_b = ChaCha8Rng::from_seed(Default::default());
+            let mut genome_a = genome(&mut rng_a, &mut innovations);
+            let mut genome_b = genome(&mut rng_b, &mut innovations);
+
+            genome_a.mutate_add_node(&mut rng_a, &mut innovations, Activation::Tanh);
+            genome_b.mutate_add_node(&mut rng_b, &mut innovations, Activation::Tanh);
+
+            assert_eq!(genome_a, genome_b);
+        }
+
+        #[test]
+        fn add_connection() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut innovations = Innovations::new();
+            let mut genome = Genome::minimal(&mut rng, &mut innovations, 3, 2, Activation::Tanh, Initializer::Xavier);
+
+            for _ in 0..10 {
+                genome.mutate_add_node(&mut rng, &mut innovations, Activation::ReLU);
+                genome.mutate_add_connection(&mut rng, &mut innovations, Initializer::Xavier);
+            }
+
+            // Connections are never duplicated...
+            let mut pairs: Vec<_> = genome
+                .connections()
+                .iter()
+                .map(|gene| (gene.from, gene.to))
+                .collect();
+            pairs.sort_unstable();
+            pairs.dedup();
+            assert_eq!(pairs.len(), genome.connections().len());
+
+            // ...and never form a cycle, so every node gets evaluated
+            let outputs = genome.compile().propagate(&[0.5, -1.0, 2.0]);
+            assert_eq!(outputs.len(), 2);
+            assert!(outputs.iter().all(|output| output.is_finite()));
+        }
+
+        #[test]
+        fn add_connection_when_full() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut innovations = Innovations::new();
+            let mut genome = genome(&mut rng, &mut innovations);
+
+            assert!(!genome.mutate_add_connection(&mut rng, &mut innovations, HALF));
+        }
+
+        #[test]
+        fn disable_connection() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut genome = genome(&mut rng, &mut Innovations::new());
+
+            assert!(genome.mutate_disable_connection(&mut rng));
+            assert!(genome.mutate_disable_connection(&mut rng));
+            assert!(!genome.mutate_disable_connection(&mut rng));
+
+            // Only the bias is left
+            approx::assert_relative_eq!(genome.compile().propagate(&[1.0, 3.0])[0], 0.5);
+        }
+
+        #[test]
+        fn crossover() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut innovations = Innovations::new();
+            let mut fitter = genome(&mut rng, &mut innovations);
+            let mut other = fitter.clone();
+
+            fitter.mutate_add_node(&mut rng, &mut innovations, Activation::Tanh);
+            other.mutate_weights(&mut rng, 1.0, 0.25);
+
+            let child = Genome::crossover(&mut rng, &fitter, &other);
+            let innovations = |genome: &Genome| -> Vec<_> {
+                genome.connections().iter().map(|gene| gene.innovation).collect()
+            };
+
+            // Excess genes come from the fitter parent only
+            assert_eq!(innovations(&child), innovations(&fitter));
+            assert_eq!(child.nodes().len(), fitter.nodes().len());
+
+            // Matching genes come from either parent
+            for gene in child.connections() {
+                let from_fitter = fitter.connections().iter().any(|other| other.weight == gene.weight);
+                let from_other = other.connections().iter().any(|other| other.weight == gene.weight);
+
+                assert!(from_fitter || from_other);
+            }
+        }
+
+        #[test]
+        #[should_panic(expected = "expected 2 inputs, got 1")]
+        fn input_size_mismatch() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            genome(&mut rng, &mut Innovations::new()).compile().propagate(&[1.0]);
+        }
+
+        #[test]
+        #[should_panic]
+        fn mutate_weights_needs_a_probability() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut genome = genome(&mut rng, &mut Innovations::new());
+
+            genome.mutate_weights(&mut rng, 1.5, 0.25);
+        }
+    }
+
+    mod diagram {
+        use super::*;
+
+        fn labels() -> Labels {
+            Labels::new(["cell 0", "cell \"1\""], ["speed <m/s>"])
+        }
+
+        #[test]
+        fn dot() {
+            let dot = network().to_dot(&labels());
+
+            assert!(dot.starts_with("digraph network {"));
+            assert!(dot.contains(r#"n0_0 [tooltip="", label="cell 0", shape=box];"#));
+            assert!(dot.contains(r#"label="cell \"1\"""#));
+            assert!(dot.contains(r#"n2_0 [tooltip="bias -0.200", label="speed <m/s>", shape=box];"#));
+
+            // The 4 zero weights are left out
+            assert_eq!(dot.matches(" -> ").count(), 32);
+            assert!(!dot.contains("n0_1 -> n1_2 "));
+            assert!(dot.contains(r##"n0_2 -> n1_0 [color="#2b6cb0", penwidth=4.00, tooltip="1.000"];"##));
+            assert!(dot.contains(r##"n1_0 -> n2_1 [color="#c53030", penwidth=2.60, tooltip="-0.600"];"##));
+            assert!(dot.contains(r##"n1_0 -> n1_0 [color="#2b6cb0", penwidth=1.20, tooltip="0.200", style=dashed"##));
+        }
+
+        #[test]
+        fn svg() {
+            let svg = network().to_svg(&labels());
+
+            assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg""#));
+            assert!(svg.trim_end().ends_with("</svg>"));
+            assert_eq!(svg.matches("<circle").count(), 9);
+            assert_eq!(svg.matches("<line").count(), 18);
+            assert_eq!(svg.matches("<path").count(), 14);
+            assert!(svg.contains(">speed &lt;m/s&gt;</text>"));
+            assert!(svg.contains(r#">cell "1"</text>"#));
+        }
+
+        #[test]
+        fn default_labels() {
+            let dot = network().to_dot(&Labels::default());
+
+            assert!(dot.contains(r#"label="in 1""#));
+            assert!(dot.contains(r#"label="out 0""#));
+        }
+    }
+
+    mod prune {
+        use super::*;
+
+        #[test]
+        fn report() {
+            let mut network = network();
+            let report = network.prune(0.5);
+
+            // 4 * (3 + 4) input and recurrent weights, then 2 * 4 output weights
+            assert_eq!(report.connections, 36);
+            assert_eq!(report, PruneReport { connections: 36, survivors: 20 });
+            approx::assert_relative_eq!(report.survival_rate(), 20.0 / 36.0);
+
+            // Weak weights are zeroed, biases are kept
+            for (layer, original) in network.layers().iter().zip(self::network().layers()) {
+                let weights = layer.weights().iter().chain(layer.recurrent_weights());
+
+                for weight in weights {
+                    assert!(*weight == 0.0 || weight.abs() >= 0.5);
+                }
+
+                assert_eq!(layer.biases(), original.biases());
+            }
+
+            assert_eq!(network.to_sparse().connections(), 20);
+        }
+
+        #[test]
+        fn sparse_propagate() {
+            let mut network = network();
+            network.prune(0.5);
+
+            let mut sparse = network.to_sparse();
+
+            // Also checks that the recurrent state is carried over
+            for inputs in [[0.1, -0.4, 0.9], [1.0, 0.5, -2.0], [1.0, 0.5, -2.0]] {
+                let expected = network.propagate(inputs.to_vec());
+                let actual = sparse.propagate(&inputs);
+
+                approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+            }
+        }
+
+        #[test]
+        #[should_panic(expected = "expected 3 inputs, got 2")]
+        fn input_size_mismatch() {
+            network().to_sparse().propagate(&[1.0, 2.0]);
+        }
+    }
+
+    mod quantize {
+        use super::*;
+
+        #[test]
+        fn scales() {
+            let scales = network().quantize().scales();
+
+            // The largest weight of both layers is 1.0
+            approx::assert_relative_eq!(scales.as_slice(), [1.0 / 127.0; 2].as_ref());
+        }
+
+        #[test]
+        fn propagate() {
+            let mut network = network();
+            let mut quantized = network.quantize();
+            let inputs = [[0.1, -0.4, 0.9], [1.0, 0.5, -2.0], [1.0, 0.5, -2.0], [-0.3, 0.0, 0.7]];
+
+            let error = quantized.max_error(&mut network, inputs.iter().map(|inputs| inputs.as_slice()));
+
+            assert!(error > 0.0);
+            assert!(error < 0.02, "max error {}", error);
+        }
+
+        #[test]
+        fn zero_weights() {
+            let network = Network::new(vec![
+                Layer::new(vec![Neuron::new(0.5, vec![0.0, 0.0])], Activation::Identity),
+                Layer::new(vec![Neuron::new(-1.0, vec![2.0])], Activation::Identity),
+            ]);
+            let mut quantized = network.quantize();
+
+            assert_eq!(quantized.scales(), [1.0, 2.0 / 127.0]);
+            approx::assert_relative_eq!(quantized.propagate(&[3.0, 4.0])[0], 0.0);
+        }
+
+        #[cfg(feature = "serde")]
+        #[test]
+        fn serde() {
+            let mut network = network();
+            network.propagate(vec![0.1, -0.4, 0.9]);
+
+            let mut quantized = network.quantize();
+            let json = serde_json::to_string(&quantized).unwrap();
+            let mut copy: QuantizedNetwork = serde_json::from_str(&json).unwrap();
+
+            // The copy carries on from the same state
+            for inputs in [[1.0, 0.5, -2.0], [-0.3, 0.0, 0.7]] {
+                assert_eq!(copy.propagate(&inputs), quantized.propagate(&inputs));
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        #[test]
+        fn serde_bincode() {
+            let mut quantized = network().quantize();
+            let bytes = bincode::serialize(&quantized).unwrap();
+            let mut copy: QuantizedNetwork = bincode::deserialize(&bytes).unwrap();
+
+            assert_eq!(copy.propagate(&[1.0, 0.5, -2.0]), quantized.propagate(&[1.0, 0.5, -2.0]));
+        }
+
+        #[cfg(feature = "serde")]
+        #[test]
+        fn serde_header() {
+            let json = serde_json::to_value(network().quantize()).unwrap();
+
+            assert_eq!(json["format"], "lib-neural-network-int8");
+            assert_eq!(json["version"], 1);
+
+            // A float network is not a quantized one, and the other way round
+            let float = serde_json::to_value(network()).unwrap();
+            assert!(serde_json::from_value::<QuantizedNetwork>(float).is_err());
+            assert!(serde_json::from_value::<Network>(json.clone()).is_err());
+
+            let mut newer = json;
+            newer["version"] = 2.into();
+            assert!(serde_json::from_value::<QuantizedNetwork>(newer).is_err());
+        }
+
+        #[cfg(feature = "serde")]
+        #[test]
+        fn serde_checks_sizes() {
+            let layer = |input_size: usize, weights: &[i8], state: &[f32]| serde_json::json!({
+                "input_size": input_size,
+                "weights": weights,
+                "recurrent_weights": vec![1; state.len() * weights.len() / input_size],
+                "scale": 0.01,
+                "biases": vec![0.0; weights.len() / input_size],
+                "activation": "Identity",
+                "state": state,
+            });
+            let network = |layers: Vec<serde_json::Value>| serde_json::json!({
+                "format": "lib-neural-network-int8",
+                "version": 1,
+                "layers": layers,
+            });
+            let load = |json| serde_json::from_value::<QuantizedNetwork>(json);
+
+            assert!(load(network(vec![layer(2, &[1, 2], &[]), layer(1, &[3], &[0.5])])).is_ok());
+
+            // Too few weights for two inputs
+            let mut short = layer(2, &[1, 2], &[]);
+            short["weights"] = serde_json::json!([1]);
+            assert!(load(network(vec![short])).is_err());
+
+            // Recurrent weights without the state they apply to
+            let mut stateless = layer(1, &[3], &[0.5]);
+            stateless["state"] = serde_json::json!([]);
+            assert!(load(network(vec![stateless])).is_err());
+
+            // Layers which don't chain together
+            assert!(load(network(vec![layer(2, &[1, 2], &[]), layer(2, &[3, 4], &[])])).is_err());
+            assert!(load(network(vec![])).is_err());
+
+            let mut unscaled = layer(2, &[1, 2], &[]);
+            unscaled["scale"] = serde_json::json!(0.0);
+            assert!(load(network(vec![unscaled])).is_err());
+        }
+    }
+
+    mod backprop {
+        use super::*;
+        use rand::{Rng, SeedableRng};
+        use rand_chacha::ChaCha8Rng;
+
+        /// Loss of a network with given weights and recurrent states.
+        fn loss(weights: &[f32], states: &[Vec<f32>], inputs: &[f32], targets: &[f32]) -> f32 {
+            let mut network = Network::from_weights(&topology(), weights.to_vec());
+
+            for (layer, state) in network.layers.iter_mut().zip(states) {
+                layer.remember(state);
+            }
+
+            mse(&network.propagate(inputs.to_vec()), targets)
+        }
+
+        #[test]
+        fn gradients() {
+            let weights: Vec<_> = super::weights(0).collect();
+            let mut network = network();
+            let (inputs, targets) = ([0.3, -0.8, 0.5], [0.2, 0.9]);
+
+            // A first propagation, so that the recurrent weights matter too
+            network.propagate(vec![1.0, 0.5, -0.5]);
+
+            let states: Vec<_> = network.layers().iter().map(|layer| layer.state().to_vec()).collect();
+            let (loss_value, gradients) = network.backpropagate(&inputs, &targets);
+
+            approx::assert_relative_eq!(loss_value, loss(&weights, &states, &inputs, &targets));
+
+            // Central finite differences, with the same states
+            for (index, gradient) in gradients.values().iter().enumerate() {
+                let epsilon = 1e-2;
+                let mut plus = weights.clone();
+                let mut minus = weights.clone();
+                plus[index] += epsilon;
+                minus[index] -= epsilon;
+
+                let expected = (loss(&plus, &states, &inputs, &targets)
+                    - loss(&minus, &states, &inputs, &targets)) / (2.0 * epsilon);
+
+                approx::assert_abs_diff_eq!(*gradient, expected, epsilon = 1e-3);
+            }
+        }
+
+        fn distil(optimizer: &mut dyn Optimizer, epochs: usize) -> (f32, f32) {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let layers = [
+                LayerTopology { neurons: 3, activation: Activation::Identity, kind: LayerKind::Dense },
+                LayerTopology { neurons: 6, activation: Activation::Tanh, kind: LayerKind::Dense },
+                LayerTopology { neurons: 2, activation: Activation::Identity, kind: LayerKind::Dense },
+            ];
+            let mut teacher = Network::random(&mut rng, &layers, Initializer::default());
+            let mut student = Network::random(&mut rng, &layers, Initializer::default());
+
+            let inputs: Vec<Vec<f32>> = (0..64)
+                .map(|_| (0..3).map(|_| rng.gen_range(-1.0..=1.0)).collect())
+                .collect();
+            let targets: Vec<_> = inputs.iter().map(|inputs| teacher.propagate(inputs.clone())).collect();
+            let samples = || inputs.iter().zip(&targets).map(|(i, t)| (i.as_slice(), t.as_slice()));
+
+            let first = student.train_batch(optimizer, samples());
+            let mut last = first;
+
+            for _ in 0..epochs {
+                last = student.train_batch(optimizer, samples());
+            }
+
+            // The weights keep the layout of chromosomes
+            let mut copy = Network::from_weights(&layers, student.weights());
+            assert_eq!(copy.propagate(inputs[0].clone()), student.propagate(inputs[0].clone()));
+
+            (first, last)
+        }
+
+        #[test]
+        fn sgd() {
+            let (first, last) = distil(&mut Sgd::new(0.1), 300);
+
+            assert!(last < first / 100.0, "loss went from {} to {}", first, last);
+        }
+
+        #[test]
+        fn adam() {
+            let (first, last) = distil(&mut Adam::new(0.02), 300);
+
+            assert!(last < first / 300.0, "loss went from {} to {}", first, last);
+        }
+
+        #[test]
+        #[should_panic(expected = "expected 2 targets, got 1")]
+        fn target_size_mismatch() {
+            network().backpropagate(&[1.0, 2.0, 3.0], &[1.0]);
+        }
+    }
+
+    mod scalar {
+        use super::*;
+
+        /// Propagates the same inputs through the `f32` network and its `S` counterpart,
+        /// whose output goes through `exp` as well.
+        fn check<S: Scalar>(epsilon: f32) {
+            let mut layers = topology();
+            layers[2].activation = Activation::Sigmoid;
+
+            let mut expected = Network::from_weights(&layers, weights(0));
+            let mut actual = Network::from_weights(&layers, weights(0).map(S::from_f32));
+
+            let expected_weights = expected.weights();
+            let actual_weights: Vec<_> = actual.weights().into_iter().map(S::to_f32).collect();
+            approx::assert_abs_diff_eq!(
+                actual_weights.as_slice(),
+                expected_weights.as_slice(),
+                epsilon = epsilon
+            );
+
+            for inputs in [[0.1, -0.4, 0.9], [1.0, 0.5, -2.0], [1.0, 0.5, -2.0]] {
+                let expected = expected.propagate(inputs.to_vec());
+                let actual: Vec<_> = actual
+                    .propagate(inputs.iter().copied().map(S::from_f32).collect())
+                    .into_iter()
+                    .map(S::to_f32)
+                    .collect();
+
+                approx::assert_abs_diff_eq!(actual.as_slice(), expected.as_slice(), epsilon = epsilon);
+            }
+        }
+
+        #[test]
+        fn f64() {
+            check::<f64>(1e-6);
+        }
+
+        #[test]
+        fn fixed() {
+            check::<Fixed>(1e-3);
+        }
+
+        #[test]
+        fn fixed_arithmetic() {
+            let x = Fixed::from_f32(1.5);
+            let y = Fixed::from_f32(-0.25);
+
+            assert_eq!(x.to_bits(), 3 << 15);
+            assert_eq!((x + y).to_f32(), 1.25);
+            assert_eq!((x - y).to_f32(), 1.75);
+            assert_eq!((x * y).to_f32(), -0.375);
+            assert_eq!((x / y).to_f32(), -6.0);
+            assert_eq!((-x).to_f32(), -1.5);
+
+            // Saturates instead of overflowing
+            assert_eq!(Fixed::MAX + x, Fixed::MAX);
+            assert_eq!(Fixed::from_f32(30000.0) * Fixed::from_f32(-2.0), Fixed::MIN);
+            assert_eq!(x / Fixed::ZERO, Fixed::MAX);
+        }
+
+        #[test]
+        fn fixed_functions() {
+            // exp(10.4) is already beyond the largest Q16.16 number
+            for n in -120..=103 {
+                let x = n as f32 / 10.0;
+                let fixed = Fixed::from_f32(x);
+
+                approx::assert_abs_diff_eq!(Scalar::tanh(fixed).to_f32(), x.tanh(), epsilon = 1e-4);
+                approx::assert_relative_eq!(Scalar::exp(fixed).to_f32(), x.exp(), epsilon = 1e-4, max_relative = 1e-3);
+            }
+
+            assert_eq!(Scalar::exp(Fixed::from_f32(11.0)), Fixed::MAX);
+            assert_eq!(Scalar::exp(Fixed::from_f32(-12.0)), Fixed::ZERO);
+        }
+    }
+
+    mod distance {
+        use super::*;
+
+        /// The shared network, with each weight of `changes` moved by the given amount.
+        fn shifted(changes: &[(usize, f32)]) -> Network {
+            let mut weights: Vec<_> = weights(0).collect();
+
+            for &(index, change) in changes {
+                weights[index] += change;
+            }
+
+            Network::from_weights(&topology(), weights)
+        }
+
+        #[test]
+        fn clone_is_equal() {
+            let mut network = network();
+            network.propagate(vec![1.0, 0.5, -0.5]);
+
+            let clone = network.clone();
+
+            assert_eq!(clone, network);
+            assert_eq!(clone.layers()[0].state(), network.layers()[0].state());
+            assert_ne!(clone, shifted(&[(41, 0.1)]));
+        }
+
+        #[test]
+        fn equality_ignores_state() {
+            let mut network = network();
+            let clone = network.clone();
+
+            network.propagate(vec![1.0, 0.5, -0.5]);
+
+            assert_ne!(clone.layers()[0].state(), network.layers()[0].state());
+            assert_eq!(clone, network);
+        }
+
+        #[test]
+        fn weight_distance() {
+            let a = network();
+            let b = shifted(&[(0, 3.0), (41, 4.0)]);
+
+            approx::assert_relative_eq!(a.weight_distance(&b), 5.0, epsilon = 1e-6);
+            approx::assert_relative_eq!(a.weight_distance(&a.clone()), 0.0);
+        }
+
+        #[test]
+        fn weight_distance_topology_mismatch() {
+            let a = network();
+            let b = Network::new(vec![
+                Layer::new(vec![Neuron::new(0.0, vec![0.0])], Activation::Tanh),
+                Layer::new(vec![Neuron::new(0.0, vec![0.0])], Activation::Identity),
+            ]);
+
+            assert_eq!(a.try_weight_distance(&b), Err(NetworkError::TopologyMismatch));
+        }
+
+        #[test]
+        fn behavioural_distance() {
+            // Only the biases of the outputs differ, by 0.3 and 0.4, so the outputs are always 0.5 apart
+            let a = network();
+            let b = shifted(&[(32, 0.3), (37, 0.4)]);
+            let probes = [[1.0, 0.5, -0.5], [-0.5, 0.0, 0.2], [0.2, 0.3, 1.0]];
+
+            let actual = a.behavioural_distance(&b, probes.iter().map(|probe| probe.as_slice()));
+
+            approx::assert_relative_eq!(actual, 0.5, epsilon = 1e-6);
+        }
+
+        #[test]
+        fn behavioural_distance_leaves_states_untouched() {
+            let mut a = network();
+            let b = Network::from_weights(&topology(), weights(1));
+            a.propagate(vec![1.0, 0.5, -0.5]);
+
+            let state = a.layers()[0].state().to_vec();
+            let probes = [[1.0, 0.5, -0.5], [-0.5, 0.0, 0.2]];
+            let distance = a.behavioural_distance(&b, probes.iter().map(|probe| probe.as_slice()));
+
+            assert!(distance > 0.0);
+            assert_eq!(a.layers()[0].state(), state);
+            assert_eq!(b.layers()[0].state(), vec![0.0; 4]);
+
+            // The probes start from an empty state, whatever the networks remember
+            let again = a.behavioural_distance(&b, probes.iter().map(|probe| probe.as_slice()));
+            approx::assert_relative_eq!(again, distance);
+        }
+
+        #[test]
+        fn behavioural_distance_output_size_mismatch() {
+            let a = network();
+            let b = Network::new(vec![
+                Layer::new(vec![Neuron::new(0.0, vec![0.0; 3])], Activation::Tanh),
+                Layer::new(vec![Neuron::new(0.0, vec![0.0])], Activation::Identity),
+            ]);
+
+            assert_eq!(
+                a.try_behavioural_distance(&b, [[1.0, 0.5, -0.5].as_slice()]),
+                Err(NetworkError::OutputSizeMismatch { expected: 2, actual: 1 })
+            );
+        }
+    }
+
+    mod hebbian {
+        use super::*;
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha8Rng;
+
+        #[test]
+        fn delta() {
+            let rule = HebbianRule { learning_rate: 0.5, a: 1.0, b: 2.0, c: 3.0, d: 4.0 };
+
+            // 0.5 * (1 * 2 * 3 + 2 * 2 + 3 * 3 + 4)
+            approx::assert_relative_eq!(rule.delta(2.0, 3.0), 11.5);
+        }
+
+        #[test]
+        fn weights_round_trip() {
+            let rule = HebbianRule { learning_rate: 0.1, a: 0.2, b: 0.3, c: 0.4, d: 0.5 };
+            let actual = HebbianRule::from_weights(&mut rule.weights().into_iter());
+
+            assert_eq!(actual, rule);
+        }
+
+        #[test]
+        fn updates_weights_after_propagating() {
+            let mut network = Network::new(vec![
+                Layer::new(vec![Neuron::new(0.1, vec![0.5, -0.5])], Activation::Identity),
+                Layer::new(vec![Neuron::new(-0.2, vec![2.0])], Activation::Identity),
+            ]);
+            let rules = [
+                HebbianRule { learning_rate: 0.1, a: 1.0, ..Default::default() },
+                HebbianRule { learning_rate: 1.0, d: 0.5, ..Default::default() },
+            ];
+
+            // The first layer outputs 0.1 + 0.5 - 0.25 = 0.35, the second one 0.7 - 0.2 = 0.5
+            let outputs = network.propagate_plastic(&[1.0, 0.5], &rules);
+            approx::assert_relative_eq!(outputs.as_slice(), [0.5].as_slice());
+
+            let actual = network.weights();
+            let expected = [0.1, 0.5 + 0.1 * 1.0 * 0.35, -0.5 + 0.1 * 0.5 * 0.35, -0.2, 2.5];
+            approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+        }
+
+        #[test]
+        fn weights_stay_within_limit() {
+            let mut network = network();
+            let rules = [
+                HebbianRule { learning_rate: 1.0, d: 100.0, ..Default::default() },
+                HebbianRule { learning_rate: 1.0, d: -100.0, ..Default::default() },
+            ];
+
+            network.propagate_plastic(&[1.0, 0.5, -0.5], &rules);
+
+            // Recurrent weights included, biases aside
+            for (layer, limit) in network.layers().iter().zip([WEIGHT_LIMIT, -WEIGHT_LIMIT]) {
+                for weight in layer.weights().iter().chain(layer.recurrent_weights()) {
+                    approx::assert_relative_eq!(*weight, limit);
+                }
+            }
+
+            let original = self::network();
+            for (layer, original) in network.layers().iter().zip(original.layers()) {
+                assert_eq!(layer.biases(), original.biases());
+            }
+        }
+
+        #[test]
+        fn without_learning_rate_acts_like_propagate() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut plastic = Network::random(&mut rng, &topology(), Initializer::default());
+            let mut frozen = Network::from_weights(&topology(), plastic.weights());
+            let rules = [
+                HebbianRule { learning_rate: 0.0, ..HebbianRule::random(&mut rng) },
+                HebbianRule { learning_rate: 0.0, ..HebbianRule::random(&mut rng) },
+            ];
+
+            for inputs in [[1.0, 0.5, -0.5], [0.3, -0.8, 0.5], [0.0, 0.2, 0.9]] {
+                let expected = frozen.propagate(inputs.to_vec());
+                let actual = plastic.propagate_plastic(&inputs, &rules);
+
+                approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+            }
+
+            let actual = plastic.weights();
+            let expected = frozen.weights();
+            approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+        }
+
+        #[test]
+        fn recurrent_weights_learn_from_the_previous_outputs() {
+            let mut network = Network::new(vec![
+                Layer::recurrent(vec![Neuron::new(0.0, vec![1.0, 0.5])], Activation::Identity),
+                Layer::new(vec![Neuron::new(0.0, vec![1.0])], Activation::Identity),
+            ]);
+            let rules = [
+                HebbianRule { learning_rate: 1.0, a: 1.0, ..Default::default() },
+                HebbianRule::default(),
+            ];
+
+            // The first propagation sees an empty state, so only the input weight moves: 1 + 2 * 2
+            network.propagate_plastic(&[2.0], &rules);
+            // Then the layer outputs 5 * 1 + 0.5 * 2 = 6, and both weights move, up to the limit
+            network.propagate_plastic(&[1.0], &rules);
+
+            let actual = network.layers()[0].weights().to_vec();
+            approx::assert_relative_eq!(actual.as_slice(), [WEIGHT_LIMIT].as_slice());
+
+            let actual = network.layers()[0].recurrent_weights().to_vec();
+            approx::assert_relative_eq!(actual.as_slice(), [WEIGHT_LIMIT].as_slice());
+        }
+
+        #[test]
+        fn rule_count_mismatch() {
+            let mut network = network();
+
+            assert_eq!(
+                network.try_propagate_plastic(&[1.0, 0.5, -0.5], &[HebbianRule::default()]),
+                Err(NetworkError::RuleCountMismatch { expected: 2, actual: 1 })
+            );
         }
     }
 
@@ -57,8 +1471,14 @@ mod tests {
         #[test]
         fn test() {
             let network = Network::new(vec![
-                Layer::new(vec![Neuron::new(0.1, vec![0.2, 0.3, 0.4])]),
-                Layer::new(vec![Neuron::new(0.5, vec![0.6, 0.7, 0.8])])
+                Layer::new(
+                    vec![Neuron::new(0.1, vec![0.2, 0.3, 0.4])],
+                    Activation::ReLU
+                ),
+                Layer::new(
+                    vec![Neuron::new(0.5, vec![0.6]), Neuron::new(0.7, vec![0.8])],
+                    Activation::ReLU
+                )
             ]);
 
             let actual = network.weights();
@@ -77,8 +1497,8 @@ mod tests {
         #[test]
         fn test() {
             let layers = &[
-                LayerTopology { neurons: 3 },
-                LayerTopology { neurons: 2 },
+                LayerTopology { neurons: 3, activation: Activation::ReLU, kind: LayerKind::Dense },
+                LayerTopology { neurons: 2, activation: Activation::Tanh, kind: LayerKind::Dense },
             ];
 
             let weights = vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8];
@@ -90,6 +1510,7 @@ mod tests {
                 actual.as_slice(),
                 weights.as_slice()
             );
+            assert_eq!(network.layers[0].activation(), Activation::Tanh);
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,67 @@
+use crate::*;
+
+/// Everything computed by the layers of a network during one propagation.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Trace {
+    pub layers: Vec<LayerTrace>,
+}
+
+impl Trace {
+    /// Outputs of the network, i.e. the activations of its last layer.
+    pub fn outputs(&self) -> &[f32] {
+        &self.layers[self.layers.len() - 1].activations
+    }
+}
+
+/// What the neurons of one layer computed during a propagation.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LayerTrace {
+    /// Weighted sum of the inputs of each neuron, bias included
+    pub pre_activations: Vec<f32>,
+    /// Output of each neuron, after the activation function
+    pub activations: Vec<f32>,
+}
+
+impl Network {
+    /// Panicking version of `try_propagate_traced`.
+    pub fn propagate_traced(&mut self, inputs: &[f32]) -> Trace {
+        self.try_propagate_traced(inputs).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as `try_propagate`, but also returns the values computed by every layer,
+    /// e.g. to see which neurons fire for a given input.
+    /// Recurrent layers move on to their next state, just like with a regular propagation.
+    pub fn try_propagate_traced(&mut self, inputs: &[f32]) -> Result<Trace, NetworkError> {
+        self.check_inputs(inputs)?;
+
+        let mut layers: Vec<LayerTrace> = Vec::with_capacity(self.layers.len());
+
+        for layer in &mut self.layers {
+            let inputs = layers.last().map_or(inputs, |trace| &trace.activations);
+            let trace = layer.propagate_traced(inputs);
+
+            layers.push(trace);
+        }
+
+        Ok(Trace { layers })
+    }
+}
+
+impl Layer {
+    fn propagate_traced(&mut self, inputs: &[f32]) -> LayerTrace {
+        let pre_activations: Vec<_> = (0..self.output_size())
+            .map(|neuron| self.pre_activation(inputs, neuron))
+            .collect();
+
+        let activations: Vec<_> = pre_activations
+            .iter()
+            .map(|&x| self.activation.apply(x))
+            .collect();
+
+        self.remember(&activations);
+
+        LayerTrace { pre_activations, activations }
+    }
+}
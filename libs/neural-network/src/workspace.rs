@@ -0,0 +1,16 @@
+use crate::*;
+
+/// Buffers reused by `Network::propagate_into` between calls,
+/// so that propagating doesn't allocate once they have grown to size.
+/// A single workspace can be shared by networks of different shapes.
+#[derive(Clone, Debug, Default)]
+pub struct Workspace<S = f32> {
+    pub(crate) front: Vec<S>,
+    pub(crate) back: Vec<S>,
+}
+
+impl<S: Scalar> Workspace<S> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
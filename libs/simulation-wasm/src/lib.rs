@@ -21,12 +21,7 @@ impl Simulation {
         let mut rng = thread_rng();
         let sim = sim::Simulation::random(&mut rng);
 
-        let birds = sim.world().birds();
-        let birds_individual: Vec<lib_simulation::BirdIndividual> = birds
-            .iter()
-            .map(|bird| lib_simulation::BirdIndividual::from_bird(bird))
-            .collect();
-        let stats = ga::Statistics::new(&birds_individual);
+        let stats = sim.statistics();
 
         Self { rng, sim, generation: 0, stats }
     }
@@ -70,6 +65,12 @@ impl Simulation {
     }
 }
 
+impl Default for Simulation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Front-end World
 #[derive(Clone, Debug, Serialize)]
 pub struct World {
@@ -0,0 +1,33 @@
+//! Reports how lifetime learning birds fare against purely evolved ones,
+//! over a few generations from the same seed.
+//!
+//! Run with `cargo run --release --example lifetime_learning [generations]`.
+
+use lib_simulation::{Plasticity, Simulation};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+fn main() {
+    let generations = std::env::args()
+        .nth(1)
+        .map_or(10, |arg| arg.parse().expect("The number of generations must be an integer"));
+
+    for plasticity in [Plasticity::Frozen, Plasticity::Hebbian] {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut simulation = Simulation::with_plasticity(plasticity, &mut rng);
+
+        println!("{:?} birds", plasticity);
+
+        for generation in 0..generations {
+            let stats = simulation.train(&mut rng);
+
+            println!(
+                "  generation {:>3}: min = {:>5.1}, avg = {:>5.1}, max = {:>5.1}",
+                generation,
+                stats.min_fitness(),
+                stats.avg_fitness(),
+                stats.max_fitness()
+            );
+        }
+    }
+}
@@ -26,9 +26,9 @@ impl Bird {
     }
 
     /// Initializes a new bird at a random position, with a default eye and a random brain.
-    pub fn random(rng: &mut dyn RngCore) -> Self {
+    pub fn random(rng: &mut dyn RngCore, plasticity: Plasticity) -> Self {
         let eye = Eye::default();
-        let brain = Brain::random(&eye);
+        let brain = Brain::random(rng, &eye, plasticity);
 
         Self {
             position: rng.gen(),
@@ -50,6 +50,16 @@ impl Bird {
         self.rotation
     }
 
+    /// Getter for the eye of the bird
+    pub fn eye(&self) -> &Eye {
+        &self.eye
+    }
+
+    /// Getter for the brain of the bird
+    pub fn brain(&self) -> &Brain {
+        &self.brain
+    }
+
     /// Convert the bird to its chromosome
     pub(crate) fn as_chromosome(&self) -> ga::Chromosome {
         self.brain.as_chromosome()
@@ -1,36 +1,148 @@
 use crate::*;
 
+/// Whether the brains keep the weights they were born with,
+/// or keep learning during the lifetime of the birds.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Plasticity {
+    /// The weights only change from one generation to the next
+    #[default]
+    Frozen,
+    /// The weights follow evolved Hebbian rules, one per layer
+    Hebbian,
+}
+
 /// A wrapped for the neural network, 
 /// with functions to convert to and from chromosome
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Brain {
-    pub(crate) neural_network: nn::Network
+    pub(crate) neural_network: nn::Network,
+    /// The rules of a plastic brain, empty if the brain is frozen
+    pub(crate) rules: Vec<nn::HebbianRule>,
+    /// The weights the brain was born with: offspring of plastic brains inherit those,
+    /// not the learned ones
+    pub(crate) inherited_weights: Vec<f32>,
+    /// How much each gene of the brain mutates, evolving along with the genes
+    pub(crate) step_sizes: Vec<f32>
 }
 
 impl Brain {
     /// Initializes a random brain
-    pub fn random(eye: &Eye) -> Self {
+    pub fn random(rng: &mut dyn RngCore, eye: &Eye, plasticity: Plasticity) -> Self {
+        // Xavier keeps the recurrent layer from amplifying small differences of its state,
+        // which uniform weights in `-1.0..=1.0` do for as many neurons
+        let neural_network = nn::Network::random(
+            rng,
+            &Self::topology(eye),
+            nn::Initializer::Xavier
+        );
+
+        let rules = match plasticity {
+            Plasticity::Frozen => Vec::new(),
+            Plasticity::Hebbian => neural_network
+                .layers()
+                .iter()
+                .map(|_| nn::HebbianRule::random(rng))
+                .collect(),
+        };
+
+        let genes = neural_network.weights().len() + rules.len() * nn::HebbianRule::GENES;
+        let step_sizes = vec![INITIAL_STEP_SIZE; STEP_SIZES.count(genes)];
+
+        Self::new(neural_network, rules, step_sizes)
+    }
+
+    fn new(
+        neural_network: nn::Network,
+        rules: Vec<nn::HebbianRule>,
+        step_sizes: Vec<f32>
+    ) -> Self {
         Self {
-            neural_network: nn::Network::random(&Self::topology(eye))
+            inherited_weights: neural_network.weights(),
+            neural_network,
+            rules,
+            step_sizes
+        }
+    }
+
+    /// Whether the brain learns during the lifetime of its bird
+    pub fn plasticity(&self) -> Plasticity {
+        if self.rules.is_empty() {
+            Plasticity::Frozen
+        } else {
+            Plasticity::Hebbian
+        }
+    }
+
+    /// Given what the bird sees, returns its change of speed and rotation,
+    /// then lets a plastic brain learn from it
+    pub(crate) fn propagate(&mut self, vision: &[f32]) -> Vec<f32> {
+        if self.rules.is_empty() {
+            self.neural_network.propagate(vision.to_vec())
+        } else {
+            self.neural_network.propagate_plastic(vision, &self.rules)
         }
     }
 
-    /// Converts the brain to chromosome
+    /// Converts the brain to chromosome. Plastic brains give the weights they were born with,
+    /// followed by the coefficients of their rules. The step sizes of the mutation come last
     pub(crate) fn as_chromosome(&self) -> ga::Chromosome {
-        self.neural_network.weights().into_iter().collect()
+        let weights = match self.plasticity() {
+            Plasticity::Frozen => self.neural_network.weights(),
+            Plasticity::Hebbian => self.inherited_weights.clone(),
+        };
+
+        weights
+            .into_iter()
+            .chain(self.rules.iter().flat_map(nn::HebbianRule::weights))
+            .chain(self.step_sizes.iter().copied())
+            .collect()
     }
 
-    /// Create a brain from a chromosome
+    /// Create a brain from a chromosome.
+    /// The brain is plastic if the chromosome holds genes past the weights,
+    /// leaving aside the step sizes, which don't make it into the network.
     pub(crate) fn from_chromosome(
         chromosome: ga::Chromosome,
         eye: &Eye
     ) -> Self {
-        Self {
-            neural_network: nn::Network::from_weights(
-                &Self::topology(eye), 
-                chromosome
-            )
-        }
+        let topology = Self::topology(eye);
+        let count = STEP_SIZES.genes(chromosome.len());
+        let mut genes = chromosome.into_iter();
+
+        let neural_network = nn::Network::from_weights(
+            &topology,
+            genes.by_ref().take(nn::LayerTopology::weights_count(&topology))
+        );
+
+        let rule_genes: Vec<_> = genes
+            .by_ref()
+            .take(count - neural_network.weights().len())
+            .collect();
+
+        let rules = rule_genes
+            .chunks(nn::HebbianRule::GENES)
+            .map(|genes| nn::HebbianRule::from_weights(&mut genes.iter().copied()))
+            .collect();
+
+        Self::new(neural_network, rules, genes.collect())
+    }
+
+    /// Renders the brain as a Graphviz DOT graph
+    pub fn to_dot(&self, eye: &Eye) -> String {
+        self.neural_network.to_dot(&Self::labels(eye))
+    }
+
+    /// Renders the brain as a standalone SVG image
+    pub fn to_svg(&self, eye: &Eye) -> String {
+        self.neural_network.to_svg(&Self::labels(eye))
+    }
+
+    /// Names of the inputs and outputs of the brain, as shown on its diagrams
+    fn labels(eye: &Eye) -> nn::Labels {
+        nn::Labels::new(
+            (0..eye.cells()).map(|cell| format!("eye cell {}", cell)),
+            ["speed", "rotation"]
+        )
     }
 
     /// Given an eye, return the layers of the associated brain
@@ -38,15 +150,23 @@ impl Brain {
         [
             // Input layer
             nn::LayerTopology {
-                neurons: eye.cells()
+                neurons: eye.cells(),
+                activation: nn::Activation::Identity,
+                kind: nn::LayerKind::Dense
             },
-            // Intermediate layer
+            // Intermediate layer, recurrent so that birds remember
+            // the food that just left their field of view.
+            // Tanh keeps the fed back outputs bounded, where ReLU could blow up.
             nn::LayerTopology {
-                neurons: 2 * eye.cells()
+                neurons: 2 * eye.cells(),
+                activation: nn::Activation::Tanh,
+                kind: nn::LayerKind::Recurrent
             },
-            // Output layer
+            // Output layer, unbounded so that the brain can slow down and turn left
             nn::LayerTopology {
-                neurons: 2
+                neurons: 2,
+                activation: nn::Activation::Identity,
+                kind: nn::LayerKind::Dense
             }
         ]
     }
@@ -25,10 +25,14 @@ const ROTATION_ACCEL: f32 = FRAC_PI_2;
 /// How many steps each bird gets to live
 const GENERATION_LENGTH: usize = 2500; 
 
-/// Gaussian Mutation chance of mutation
-const MUTATION_CHANCE: f32 = 0.01;
-/// Gaussian Mutation magnitude of mutation
-const MUTATION_COEFF: f32 = 0.03;
+/// Each gene of a brain carries its own mutation step size
+const STEP_SIZES: ga::StepSizes = ga::StepSizes::PerGene;
+/// Mutation step size of the genes of the first generation
+const INITIAL_STEP_SIZE: f32 = 0.01;
+/// Lowest mutation step size, so that evolution never stalls
+const MIN_STEP_SIZE: f32 = 0.001;
+/// How many of the best birds live on unchanged in the next generation
+const ELITISM: usize = 2;
 
 const NB_BIRDS: usize = 40;
 const NB_FOODS: usize = 60;
@@ -37,20 +41,50 @@ const NB_FOODS: usize = 60;
 pub struct Simulation {
     world: World,
     genetic_algorithm: ga::GeneticAlgorithm<ga::RouletteWheelSelection>,
-    age: usize
+    age: usize,
+    /// Whether the birds learn during their lifetime
+    plasticity: Plasticity,
+    /// The brains of every bird, propagated all at once when they are frozen
+    brains: nn::NetworkBatch,
+    /// What every bird sees, one row per bird
+    visions: Vec<f32>,
+    workspace: nn::Workspace
 }
 
 impl Simulation {
     /// Initializes a random simulation with a random world
     pub fn random(rng: &mut dyn RngCore) -> Self {
+        Self::with_plasticity(Plasticity::Frozen, rng)
+    }
+
+    /// Initializes a random simulation whose birds have the given plasticity,
+    /// so that lifetime learning can be compared against pure evolution
+    pub fn with_plasticity(plasticity: Plasticity, rng: &mut dyn RngCore) -> Self {
+        Self::from_world(World::random(NB_BIRDS, NB_FOODS, plasticity, rng))
+    }
+
+    /// Initializes a simulation starting from the given world
+    fn from_world(world: World) -> Self {
+        let brains = world.brains();
+        // Children inherit the plasticity of their parents through their chromosome
+        let plasticity = world
+            .birds
+            .first()
+            .map_or(Plasticity::Frozen, |bird| bird.brain.plasticity());
+
         Self {
-            world: World::random(NB_BIRDS, NB_FOODS, rng),
+            world,
             genetic_algorithm: ga::GeneticAlgorithm::new(
                 ga::RouletteWheelSelection::new(),
                 ga::UniformCrossover::new(),
-                ga::GaussianMutation::new(MUTATION_CHANCE, MUTATION_COEFF)
-            ),
-            age: 0
+                ga::SelfAdaptiveMutation::new(STEP_SIZES).with_min_step_size(MIN_STEP_SIZE)
+            )
+            .with_elitism(ELITISM),
+            age: 0,
+            plasticity,
+            brains,
+            visions: Vec::new(),
+            workspace: nn::Workspace::new()
         }
     }
 
@@ -59,6 +93,14 @@ impl Simulation {
         &self.world
     }
 
+    /// Statistics of the current birds, leaving out the step sizes of their chromosomes
+    pub fn statistics(&self) -> ga::Statistics {
+        let individuals: Vec<_> = self.world.birds.iter().map(BirdIndividual::from_bird).collect();
+        let genes = ga::Individual::chromosome(&individuals[0]).len();
+
+        ga::Statistics::new(&individuals, STEP_SIZES.genes(genes))
+    }
+
     /// Computes the next step of the simulation.
     /// Handles movement of the birds, collisions with food, and brains changes.
     /// Returns `Some(stats)` if `evolve` was called, `None` either.
@@ -87,30 +129,63 @@ impl Simulation {
 
     /// Adjusts the speed and rotation of each bird according to the brain
     fn process_brains(&mut self) {
-        for bird in &mut self.world.birds {
-            // What the bird sees
-            let vision = bird.eye.process_vision(
+        // What each bird sees
+        self.visions.clear();
+
+        for bird in &self.world.birds {
+            self.visions.extend(bird.eye.process_vision(
                 bird.position,
                 bird.rotation,
                 &self.world.foods
-            );
+            ));
+        }
 
-            // Response of the brain
-            let response = bird.brain.neural_network.propagate(vision);
-            let (speed, rotation) = (response[0], response[1]);
+        match self.plasticity {
+            Plasticity::Frozen => {
+                // Response of all the brains at once
+                let responses = self
+                    .brains
+                    .propagate_into(&self.visions, &mut self.workspace);
 
-            // Clamp the response to make sure that the brain doesn't change speed and rotation too much
-            let speed = speed.clamp(-SPEED_ACCEL, SPEED_ACCEL);
-            let rotation = rotation.clamp(-ROTATION_ACCEL, ROTATION_ACCEL);
+                for (bird, response) in self.world.birds.iter_mut().zip(responses.chunks_exact(2)) {
+                    Self::steer(bird, response);
+                }
 
-            // Adjusts speed and rotation
-            bird.speed = (bird.speed + speed).clamp(SPEED_MIN, SPEED_MAX);
-            bird.rotation = na::Rotation2::new(
-                bird.rotation.angle() + rotation
-            ); // no need to clamp since rotation wraps inside [0, 2*PI]
+                // Each brain keeps its own state, just like when it propagates on its own
+                self.brains.store_state(
+                    self.world.birds.iter_mut().map(|bird| &mut bird.brain.neural_network)
+                );
+            }
+            Plasticity::Hebbian => {
+                // Plastic brains drift apart from the batch, so each one learns on its own
+                let mut visions = self.visions.as_slice();
+
+                for bird in &mut self.world.birds {
+                    let (vision, rest) = visions.split_at(bird.eye.cells());
+                    visions = rest;
+
+                    let response = bird.brain.propagate(vision);
+                    Self::steer(bird, &response);
+                }
+            }
         }
     }
 
+    /// Adjusts the speed and rotation of a bird, given the response of its brain
+    fn steer(bird: &mut Bird, response: &[f32]) {
+        let (speed, rotation) = (response[0], response[1]);
+
+        // Clamp the response to make sure that the brain doesn't change speed and rotation too much
+        let speed = speed.clamp(-SPEED_ACCEL, SPEED_ACCEL);
+        let rotation = rotation.clamp(-ROTATION_ACCEL, ROTATION_ACCEL);
+
+        // Adjusts speed and rotation
+        bird.speed = (bird.speed + speed).clamp(SPEED_MIN, SPEED_MAX);
+        bird.rotation = na::Rotation2::new(
+            bird.rotation.angle() + rotation
+        ); // no need to clamp since rotation wraps inside [0, 2*PI]
+    }
+
     /// Moves the birds depending on their position, speed and rotation.
     fn process_movement(&mut self) {
         for bird in &mut self.world.birds {
@@ -148,7 +223,7 @@ impl Simulation {
             .world
             .birds
             .iter()
-            .map(|bird| BirdIndividual::from_bird(bird))
+            .map(BirdIndividual::from_bird)
             .collect();
 
         // Evolve birds
@@ -162,6 +237,7 @@ impl Simulation {
             .into_iter()
             .map(|individual| individual.into_bird(rng))
             .collect();
+        self.brains = self.world.brains();
 
         // Changes the place of the food (for UI purposes to spot a new generation)
         for food in &mut self.world.foods {
@@ -170,4 +246,245 @@ impl Simulation {
 
         stats
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    /// Every value describing the birds and the food, as raw bits.
+    fn snapshot(simulation: &Simulation) -> Vec<u32> {
+        let world = simulation.world();
+        let mut bits = Vec::new();
+
+        for bird in world.birds() {
+            bits.extend([
+                bird.position.x.to_bits(),
+                bird.position.y.to_bits(),
+                bird.rotation.angle().to_bits(),
+                bird.speed.to_bits(),
+                bird.satiation as u32,
+            ]);
+            bits.extend(bird.as_chromosome().into_iter().map(f32::to_bits));
+        }
+
+        for food in world.foods() {
+            bits.extend([food.position.x.to_bits(), food.position.y.to_bits()]);
+        }
+
+        bits
+    }
+
+    #[test]
+    fn world_without_birds() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        for plasticity in [Plasticity::Frozen, Plasticity::Hebbian] {
+            let mut simulation = Simulation::from_world(World::random(0, 20, plasticity, &mut rng));
+
+            assert!(simulation.brains.is_empty());
+            simulation.process_brains();
+        }
+    }
+
+    #[test]
+    fn same_seed_gives_same_world() {
+        let mut rng_a = ChaCha8Rng::from_seed(Default::default());
+        let mut rng_b = ChaCha8Rng::from_seed(Default::default());
+
+        // A small world keeps the test fast
+        let world_a = World::random(10, 20, Plasticity::Frozen, &mut rng_a);
+        let world_b = World::random(10, 20, Plasticity::Frozen, &mut rng_b);
+        let mut simulation_a = Simulation::from_world(world_a);
+        let mut simulation_b = Simulation::from_world(world_b);
+
+        assert_eq!(snapshot(&simulation_a), snapshot(&simulation_b));
+
+        for _ in 0..2 {
+            simulation_a.train(&mut rng_a);
+            simulation_b.train(&mut rng_b);
+        }
+
+        assert_eq!(snapshot(&simulation_a), snapshot(&simulation_b));
+    }
+
+    /// Frozen brains are propagated all at once, but each bird still holds its own state.
+    #[test]
+    fn frozen_brains_keep_their_own_state() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut simulation = Simulation::from_world(World::random(10, 20, Plasticity::Frozen, &mut rng));
+
+        simulation.step(&mut rng);
+
+        let mut networks: Vec<_> = simulation
+            .world()
+            .birds()
+            .iter()
+            .map(|bird| bird.brain.neural_network.clone())
+            .collect();
+
+        simulation.step(&mut rng);
+
+        let mut remembering = 0;
+
+        for (index, (bird, network)) in simulation.world().birds().iter().zip(&mut networks).enumerate() {
+            let cells = bird.eye.cells();
+            network.propagate(simulation.visions[index * cells..(index + 1) * cells].to_vec());
+
+            let state = bird.brain.neural_network.layers()[0].state();
+            assert_eq!(state, network.layers()[0].state());
+
+            remembering += state.iter().any(|&value| value != 0.0) as usize;
+        }
+
+        // Birds which haven't seen any food yet remember nothing
+        assert!(remembering > 0);
+    }
+
+    /// Reports how far int8 brains drift from their float version,
+    /// when both go through the visions recorded while the birds fly around.
+    #[test]
+    fn quantized_brains_match_float_brains() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut simulation = Simulation::from_world(World::random(10, 20, Plasticity::Frozen, &mut rng));
+        let mut visions = Vec::new();
+
+        for _ in 0..200 {
+            simulation.step(&mut rng);
+            visions.push(simulation.visions.clone());
+        }
+
+        let mut max_error = 0.0f32;
+
+        for (index, bird) in simulation.world().birds().iter().enumerate() {
+            let mut network = bird.brain.neural_network.clone();
+            network.reset_state();
+
+            let mut quantized = network.quantize();
+            let cells = bird.eye.cells();
+            let inputs = visions.iter().map(|visions| &visions[index * cells..(index + 1) * cells]);
+
+            max_error = max_error.max(quantized.max_error(&mut network, inputs));
+        }
+
+        assert!(max_error < 0.05, "max output error {}", max_error);
+    }
+
+    /// Distils the brain of one bird into a new brain, from recorded (vision, action) pairs,
+    /// then feeds the result back through a chromosome.
+    #[test]
+    fn distilled_brain_fits_in_a_chromosome() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut simulation = Simulation::from_world(World::random(10, 20, Plasticity::Frozen, &mut rng));
+        let mut visions = Vec::new();
+
+        for _ in 0..100 {
+            simulation.step(&mut rng);
+            visions.push(simulation.visions.clone());
+        }
+
+        let champion = &simulation.world().birds()[0];
+        let eye = &champion.eye;
+        let visions: Vec<_> = visions.iter().map(|visions| &visions[..eye.cells()]).collect();
+
+        // The champion goes through the recorded visions again, from the start
+        let mut teacher = champion.brain.neural_network.clone();
+        teacher.reset_state();
+
+        let actions: Vec<_> = visions
+            .iter()
+            .map(|vision| teacher.propagate(vision.to_vec()))
+            .collect();
+
+        let mut student = Brain::random(&mut rng, eye, Plasticity::Frozen);
+        let mut optimizer = nn::Adam::new(0.01);
+        let mut losses = Vec::new();
+
+        for _ in 0..50 {
+            student.neural_network.reset_state();
+
+            let samples = visions.iter().copied().zip(actions.iter().map(Vec::as_slice));
+            losses.push(student.neural_network.train_batch(&mut optimizer, samples));
+        }
+
+        assert!(losses[49] < losses[0] * 0.75, "loss went from {} to {}", losses[0], losses[49]);
+
+        let mut brain = Brain::from_chromosome(student.as_chromosome(), eye);
+        student.neural_network.reset_state();
+
+        for vision in &visions[..10] {
+            assert_eq!(
+                brain.neural_network.propagate(vision.to_vec()),
+                student.neural_network.propagate(vision.to_vec())
+            );
+        }
+    }
+
+    /// Plastic brains change while the birds fly around,
+    /// but the birds pass on the weights they were born with, along with their rules.
+    #[test]
+    fn plastic_birds_pass_on_inherited_weights() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let world = World::random(10, 20, Plasticity::Hebbian, &mut rng);
+        let mut simulation = Simulation::from_world(world);
+        let genes = |bird: &Bird| bird.as_chromosome().into_iter().collect::<Vec<_>>();
+        let chromosomes: Vec<_> = simulation.world().birds().iter().map(genes).collect();
+
+        for _ in 0..100 {
+            simulation.step(&mut rng);
+        }
+
+        for (bird, chromosome) in simulation.world().birds().iter().zip(&chromosomes) {
+            assert_eq!(bird.brain().plasticity(), Plasticity::Hebbian);
+            assert_eq!(&genes(bird), chromosome);
+            assert_ne!(bird.brain.neural_network.weights(), bird.brain.inherited_weights);
+        }
+
+        simulation.train(&mut rng);
+
+        for bird in simulation.world().birds() {
+            assert_eq!(bird.brain().plasticity(), Plasticity::Hebbian);
+            assert_eq!(bird.brain.rules.len(), 2);
+        }
+    }
+
+    /// The step sizes of the mutation ride along in the chromosome,
+    /// without ever making it into the network.
+    #[test]
+    fn step_sizes_stay_out_of_the_network() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let eye = Eye::default();
+
+        for plasticity in [Plasticity::Frozen, Plasticity::Hebbian] {
+            let brain = Brain::random(&mut rng, &eye, plasticity);
+            let genes = brain.neural_network.weights().len()
+                + brain.rules.len() * nn::HebbianRule::GENES;
+
+            let mut chromosome: Vec<_> = brain.as_chromosome().into_iter().collect();
+            assert_eq!(chromosome.len(), 2 * genes);
+            assert!(chromosome[genes..].iter().all(|&step_size| step_size == INITIAL_STEP_SIZE));
+
+            chromosome[genes..].fill(123.0);
+            let actual = Brain::from_chromosome(chromosome.into_iter().collect(), &eye);
+
+            assert_eq!(actual.neural_network, brain.neural_network);
+            assert_eq!(actual.rules, brain.rules);
+            assert_eq!(actual.step_sizes, vec![123.0; genes]);
+        }
+    }
+
+    #[test]
+    fn step_sizes_evolve() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut simulation = Simulation::from_world(World::random(10, 20, Plasticity::Frozen, &mut rng));
+
+        simulation.train(&mut rng);
+
+        // Elites come first, unchanged
+        for bird in &simulation.world().birds()[ELITISM..] {
+            assert!(bird.brain.step_sizes.iter().all(|&step_size| step_size >= MIN_STEP_SIZE));
+            assert!(bird.brain.step_sizes.iter().any(|&step_size| step_size != INITIAL_STEP_SIZE));
+        }
+    }
+}
@@ -8,10 +8,15 @@ pub struct World {
 }
 
 impl World {
-    /// Initializes a random world with 40 random birds and 60 random food.
-    pub fn random(nb_birds: usize, nb_foods: usize, rng: &mut dyn RngCore) -> Self {
+    /// Initializes a random world with the given number of random birds and food.
+    pub fn random(
+        nb_birds: usize,
+        nb_foods: usize,
+        plasticity: Plasticity,
+        rng: &mut dyn RngCore
+    ) -> Self {
         let birds = (0..nb_birds)
-            .map(|_| Bird::random(rng))
+            .map(|_| Bird::random(rng, plasticity))
             .collect();
 
         let foods = (0..nb_foods)
@@ -30,4 +35,13 @@ impl World {
     pub fn foods(&self) -> &[Food] {
         &self.foods
     }
+
+    /// Gathers the brains of the birds, so that they can be propagated together
+    pub(crate) fn brains(&self) -> nn::NetworkBatch {
+        nn::NetworkBatch::new(
+            self.birds
+                .iter()
+                .map(|bird| &bird.brain.neural_network)
+        )
+    }
 }
\ No newline at end of file